@@ -4,7 +4,7 @@ import * as util from 'util'
 import test, { ExecutionContext } from 'ava'
 import _ from 'lodash'
 
-import { convertAsync } from '../index'
+import { checkAsync, convertAsync, OutputFormat, OutputTarget } from '../index'
 
 const readFile = util.promisify(fs.readFile, 'utf8')
 
@@ -15,7 +15,30 @@ const inputPaths = {
   textarea: testPath + 'test_textarea.xlsx',
   multiselect: testPath + 'test_multiselect.xlsx',
   radio: testPath + 'test_radio.xlsx',
+  default: testPath + 'test_default.xlsx',
+  failDefaultOutOfRange: testPath + 'test_default_out_of_range.xlsx',
+  description: testPath + 'test_description.xlsx',
+  metadata: testPath + 'test_metadata.xlsx',
+  panel: testPath + 'test_panel.xlsx',
+  panel_dynamic: testPath + 'test_panel_dynamic.xlsx',
+  matrix: testPath + 'test_matrix.xlsx',
+  rating: testPath + 'test_rating.xlsx',
+  boolean: testPath + 'test_boolean.xlsx',
+  file: testPath + 'test_file.xlsx',
+  content: testPath + 'test_content.xlsx',
+  ranking: testPath + 'test_ranking.xlsx',
+  calculated: testPath + 'test_calculated.xlsx',
+  text_length: testPath + 'test_text_length.xlsx',
+  input_spec: testPath + 'test_input_spec.xlsx',
+  text_ods: testPath + 'test_text.ods',
+  panel_ods: testPath + 'test_panel_ods.xlsx',
+  text_xls: testPath + 'test_text.xls',
+  text_xlsb: testPath + 'test_text.xlsb',
+  panel_required_later: testPath + 'test_panel_required_later.xlsx',
+  visible_condition: testPath + 'test_visible_condition.xlsx',
   failNotExist: testPath + 'does_not_exist.xlsx',
+  failUnsupportedFormat: testPath + 'test_unsupported_format.xlsx',
+  failCorrupt: testPath + 'test_corrupt.xlsx',
   failWrongFormat: testPath + 'test_wrong_format.xlsx',
 }
 
@@ -25,6 +48,49 @@ const expectedPaths = {
   textarea: testPath + 'test_textarea_expected.json',
   multiselect: testPath + 'test_multiselect_expected.json',
   radio: testPath + 'test_radio_expected.json',
+  default: testPath + 'test_default_expected.json',
+  description: testPath + 'test_description_expected.json',
+  metadata: testPath + 'test_metadata_expected.json',
+  panel: testPath + 'test_panel_expected.json',
+  panel_dynamic: testPath + 'test_panel_dynamic_expected.json',
+  matrix: testPath + 'test_matrix_expected.json',
+  rating: testPath + 'test_rating_expected.json',
+  boolean: testPath + 'test_boolean_expected.json',
+  file: testPath + 'test_file_expected.json',
+  content: testPath + 'test_content_expected.json',
+  ranking: testPath + 'test_ranking_expected.json',
+  calculated: testPath + 'test_calculated_expected.json',
+  text_length: testPath + 'test_text_length_expected.json',
+  input_spec: testPath + 'test_input_spec_expected.json',
+  text_surveyjs: testPath + 'test_text_surveyjs_expected.json',
+  multiselect_surveyjs: testPath + 'test_multiselect_surveyjs_expected.json',
+  panel_dynamic_surveyjs: testPath + 'test_panel_dynamic_surveyjs_expected.json',
+  text_json_schema: testPath + 'test_text_json_schema_expected.json',
+  multiselect_json_schema: testPath + 'test_multiselect_json_schema_expected.json',
+  matrix_json_schema: testPath + 'test_matrix_json_schema_expected.json',
+  panel_dynamic_json_schema: testPath + 'test_panel_dynamic_json_schema_expected.json',
+  multiselect_typescript: testPath + 'test_multiselect_typescript_expected.d.ts',
+  matrix_typescript: testPath + 'test_matrix_typescript_expected.d.ts',
+  panel_dynamic_typescript: testPath + 'test_panel_dynamic_typescript_expected.d.ts',
+  description_typescript: testPath + 'test_description_typescript_expected.d.ts',
+  text_html: testPath + 'test_text_html_expected.html',
+  panel_html: testPath + 'test_panel_html_expected.html',
+  content_html: testPath + 'test_content_html_expected.html',
+  text_markdown: testPath + 'test_text_markdown_expected.md',
+  panel_dynamic_markdown: testPath + 'test_panel_dynamic_markdown_expected.md',
+  multiselect_csv: testPath + 'test_multiselect_csv_expected.csv',
+  matrix_csv: testPath + 'test_matrix_csv_expected.csv',
+  text_ir: testPath + 'test_text_ir_expected.json',
+  input_spec_ir: testPath + 'test_input_spec_ir_expected.json',
+  content_ir: testPath + 'test_content_ir_expected.json',
+  text_yaml: testPath + 'test_text_yaml_expected.yaml',
+  text_compact_json: testPath + 'test_text_compact_json_expected.json',
+  text_indent: testPath + 'test_text_indent_expected.json',
+  panel_required_later: testPath + 'test_panel_required_later_expected.json',
+  calculated_surveyjs: testPath + 'test_calculated_surveyjs_expected.json',
+  visible_condition: testPath + 'test_visible_condition_expected.json',
+  visible_condition_surveyjs: testPath + 'test_visible_condition_surveyjs_expected.json',
+  rating_html: testPath + 'test_rating_html_expected.html',
 }
 
 const outputPaths = {
@@ -33,6 +99,54 @@ const outputPaths = {
   textarea: testPath + 'test_textarea_output.json',
   multiselect: testPath + 'test_multiselect_output.json',
   radio: testPath + 'test_radio_output.json',
+  default: testPath + 'test_default_output.json',
+  description: testPath + 'test_description_output.json',
+  metadata: testPath + 'test_metadata_output.json',
+  panel: testPath + 'test_panel_output.json',
+  panel_dynamic: testPath + 'test_panel_dynamic_output.json',
+  matrix: testPath + 'test_matrix_output.json',
+  rating: testPath + 'test_rating_output.json',
+  boolean: testPath + 'test_boolean_output.json',
+  file: testPath + 'test_file_output.json',
+  content: testPath + 'test_content_output.json',
+  ranking: testPath + 'test_ranking_output.json',
+  calculated: testPath + 'test_calculated_output.json',
+  text_length: testPath + 'test_text_length_output.json',
+  input_spec: testPath + 'test_input_spec_output.json',
+  text_surveyjs: testPath + 'test_text_surveyjs_output.json',
+  multiselect_surveyjs: testPath + 'test_multiselect_surveyjs_output.json',
+  panel_dynamic_surveyjs: testPath + 'test_panel_dynamic_surveyjs_output.json',
+  text_json_schema: testPath + 'test_text_json_schema_output.json',
+  multiselect_json_schema: testPath + 'test_multiselect_json_schema_output.json',
+  matrix_json_schema: testPath + 'test_matrix_json_schema_output.json',
+  panel_dynamic_json_schema: testPath + 'test_panel_dynamic_json_schema_output.json',
+  multiselect_typescript: testPath + 'test_multiselect_typescript_output.d.ts',
+  matrix_typescript: testPath + 'test_matrix_typescript_output.d.ts',
+  panel_dynamic_typescript: testPath + 'test_panel_dynamic_typescript_output.d.ts',
+  description_typescript: testPath + 'test_description_typescript_output.d.ts',
+  text_html: testPath + 'test_text_html_output.html',
+  panel_html: testPath + 'test_panel_html_output.html',
+  content_html: testPath + 'test_content_html_output.html',
+  text_markdown: testPath + 'test_text_markdown_output.md',
+  panel_dynamic_markdown: testPath + 'test_panel_dynamic_markdown_output.md',
+  multiselect_csv: testPath + 'test_multiselect_csv_output.csv',
+  matrix_csv: testPath + 'test_matrix_csv_output.csv',
+  text_ir: testPath + 'test_text_ir_output.json',
+  input_spec_ir: testPath + 'test_input_spec_ir_output.json',
+  content_ir: testPath + 'test_content_ir_output.json',
+  text_yaml: testPath + 'test_text_yaml_output.yaml',
+  text_compact_json: testPath + 'test_text_compact_json_output.json',
+  text_indent: testPath + 'test_text_indent_output.json',
+  text_no_overwrite: testPath + 'test_text_no_overwrite_output.json',
+  text_ods: testPath + 'test_text_ods_output.json',
+  panel_ods: testPath + 'test_panel_ods_output.json',
+  text_xls: testPath + 'test_text_xls_output.json',
+  text_xlsb: testPath + 'test_text_xlsb_output.json',
+  panel_required_later: testPath + 'test_panel_required_later_output.json',
+  calculated_surveyjs: testPath + 'test_calculated_surveyjs_output.json',
+  visible_condition: testPath + 'test_visible_condition_output.json',
+  visible_condition_surveyjs: testPath + 'test_visible_condition_surveyjs_output.json',
+  rating_html: testPath + 'test_rating_html_output.html',
   failOutput: testPath + 'error.json',
 }
 
@@ -56,6 +170,235 @@ test('convert test for radio', async (t) => {
   await testConvert(t, inputPaths.radio, outputPaths.radio, expectedPaths.radio)
 })
 
+test('convert test for default values', async (t) => {
+  await testConvert(t, inputPaths.default, outputPaths.default, expectedPaths.default)
+})
+
+test('convert test for descriptions', async (t) => {
+  await testConvert(t, inputPaths.description, outputPaths.description, expectedPaths.description)
+})
+
+test('convert test for form metadata', async (t) => {
+  await testConvert(t, inputPaths.metadata, outputPaths.metadata, expectedPaths.metadata)
+})
+
+test('convert test for panels', async (t) => {
+  await testConvert(t, inputPaths.panel, outputPaths.panel, expectedPaths.panel)
+})
+
+test('convert test for dynamic panels', async (t) => {
+  await testConvert(t, inputPaths.panel_dynamic, outputPaths.panel_dynamic, expectedPaths.panel_dynamic)
+})
+
+test('convert test for matrices', async (t) => {
+  await testConvert(t, inputPaths.matrix, outputPaths.matrix, expectedPaths.matrix)
+})
+
+test('convert test for ratings', async (t) => {
+  await testConvert(t, inputPaths.rating, outputPaths.rating, expectedPaths.rating)
+})
+
+test('convert test for booleans', async (t) => {
+  await testConvert(t, inputPaths.boolean, outputPaths.boolean, expectedPaths.boolean)
+})
+
+test('convert test for file uploads', async (t) => {
+  await testConvert(t, inputPaths.file, outputPaths.file, expectedPaths.file)
+})
+
+test('convert test for static content', async (t) => {
+  await testConvert(t, inputPaths.content, outputPaths.content, expectedPaths.content)
+})
+
+test('convert test for rankings', async (t) => {
+  await testConvert(t, inputPaths.ranking, outputPaths.ranking, expectedPaths.ranking)
+})
+
+test('convert test for hidden and calculated fields', async (t) => {
+  await testConvert(t, inputPaths.calculated, outputPaths.calculated, expectedPaths.calculated)
+})
+
+test('convert test for text length', async (t) => {
+  await testConvert(t, inputPaths.text_length, outputPaths.text_length, expectedPaths.text_length)
+})
+
+test('convert test for input specifications', async (t) => {
+  await testConvert(t, inputPaths.input_spec, outputPaths.input_spec, expectedPaths.input_spec)
+})
+
+test('check returns warnings of input spec', async (t) => {
+  const warnings = await checkAsync({ inputPath: inputPaths.input_spec })
+  t.is(warnings.length, 2)
+})
+
+test('check returns no warnings for text', async (t) => {
+  const warnings = await checkAsync({ inputPath: inputPaths.text })
+  t.deepEqual(warnings, [])
+})
+
+test('convert test for the internal output target', async (t) => {
+  await testConvert(t, inputPaths.text, outputPaths.text, expectedPaths.text, 'internal')
+})
+
+test('convert test for SurveyJS output of text', async (t) => {
+  await testConvert(t, inputPaths.text, outputPaths.text_surveyjs, expectedPaths.text_surveyjs, 'surveyjs')
+})
+
+test('convert test for SurveyJS output of multiselect', async (t) => {
+  await testConvert(t, inputPaths.multiselect, outputPaths.multiselect_surveyjs, expectedPaths.multiselect_surveyjs, 'surveyjs')
+})
+
+test('convert test for SurveyJS output of panel dynamic', async (t) => {
+  await testConvert(t, inputPaths.panel_dynamic, outputPaths.panel_dynamic_surveyjs, expectedPaths.panel_dynamic_surveyjs, 'surveyjs')
+})
+
+test('convert test for JSON Schema of text answers', async (t) => {
+  await testConvert(t, inputPaths.text, outputPaths.text_json_schema, expectedPaths.text_json_schema, 'jsonSchema')
+})
+
+test('convert test for JSON Schema of multiselect answers', async (t) => {
+  await testConvert(t, inputPaths.multiselect, outputPaths.multiselect_json_schema, expectedPaths.multiselect_json_schema, 'jsonSchema')
+})
+
+test('convert test for JSON Schema of matrix answers', async (t) => {
+  await testConvert(t, inputPaths.matrix, outputPaths.matrix_json_schema, expectedPaths.matrix_json_schema, 'jsonSchema')
+})
+
+test('convert test for JSON Schema of panel dynamic answers', async (t) => {
+  await testConvert(t, inputPaths.panel_dynamic, outputPaths.panel_dynamic_json_schema, expectedPaths.panel_dynamic_json_schema, 'jsonSchema')
+})
+
+test('convert test for TypeScript types of multiselect answers', async (t) => {
+  await testConvertText(t, inputPaths.multiselect, outputPaths.multiselect_typescript, expectedPaths.multiselect_typescript, 'typescript')
+})
+
+test('convert test for TypeScript types of matrix answers', async (t) => {
+  await testConvertText(t, inputPaths.matrix, outputPaths.matrix_typescript, expectedPaths.matrix_typescript, 'typescript')
+})
+
+test('convert test for TypeScript types of panel dynamic answers', async (t) => {
+  await testConvertText(t, inputPaths.panel_dynamic, outputPaths.panel_dynamic_typescript, expectedPaths.panel_dynamic_typescript, 'typescript')
+})
+
+test('convert test for TypeScript types of description answers', async (t) => {
+  await testConvertText(t, inputPaths.description, outputPaths.description_typescript, expectedPaths.description_typescript, 'typescript')
+})
+
+test('convert test for HTML preview of text', async (t) => {
+  await testConvertText(t, inputPaths.text, outputPaths.text_html, expectedPaths.text_html, 'html')
+})
+
+test('convert test for HTML preview of panel', async (t) => {
+  await testConvertText(t, inputPaths.panel, outputPaths.panel_html, expectedPaths.panel_html, 'html')
+})
+
+test('convert test for HTML preview of content', async (t) => {
+  await testConvertText(t, inputPaths.content, outputPaths.content_html, expectedPaths.content_html, 'html')
+})
+
+test('convert test for Markdown data dictionary of text', async (t) => {
+  await testConvertText(t, inputPaths.text, outputPaths.text_markdown, expectedPaths.text_markdown, 'markdown')
+})
+
+test('convert test for Markdown data dictionary of panel dynamic', async (t) => {
+  await testConvertText(t, inputPaths.panel_dynamic, outputPaths.panel_dynamic_markdown, expectedPaths.panel_dynamic_markdown, 'markdown')
+})
+
+test('convert test for CSV data dictionary of multiselect', async (t) => {
+  await testConvertText(t, inputPaths.multiselect, outputPaths.multiselect_csv, expectedPaths.multiselect_csv, 'csv')
+})
+
+test('convert test for CSV data dictionary of matrix', async (t) => {
+  await testConvertText(t, inputPaths.matrix, outputPaths.matrix_csv, expectedPaths.matrix_csv, 'csv')
+})
+
+test('convert test for intermediate representation of text', async (t) => {
+  await testConvert(t, inputPaths.text, outputPaths.text_ir, expectedPaths.text_ir, 'ir')
+})
+
+test('convert test for intermediate representation of input spec', async (t) => {
+  await testConvert(t, inputPaths.input_spec, outputPaths.input_spec_ir, expectedPaths.input_spec_ir, 'ir')
+})
+
+test('convert test for intermediate representation of content', async (t) => {
+  await testConvert(t, inputPaths.content, outputPaths.content_ir, expectedPaths.content_ir, 'ir')
+})
+
+test('convert test for YAML output of text', async (t) => {
+  await testConvertFormat(t, inputPaths.text, outputPaths.text_yaml, expectedPaths.text_yaml, 'yaml')
+})
+
+test('convert test for compact JSON output of text', async (t) => {
+  await testConvertFormat(t, inputPaths.text, outputPaths.text_compact_json, expectedPaths.text_compact_json, 'compactJson')
+})
+
+test('convert test for JSON output of text indented by 4', async (t) => {
+  await testConvertFormat(t, inputPaths.text, outputPaths.text_indent, expectedPaths.text_indent, 'json', 4)
+})
+
+test('error on format of text target', async (t) => {
+  await t.throwsAsync(
+    convertAsync({ inputPath: inputPaths.text, outputPath: outputPaths.failOutput, target: 'typescript', format: 'yaml' }),
+  )
+})
+
+test('convert test for ods workbook of text', async (t) => {
+  await testConvert(t, inputPaths.text_ods, outputPaths.text_ods, expectedPaths.text)
+})
+
+test('convert test for ods workbook with xlsx extension', async (t) => {
+  await testConvert(t, inputPaths.panel_ods, outputPaths.panel_ods, expectedPaths.panel)
+})
+
+test('convert test for xls workbook of text', async (t) => {
+  await testConvert(t, inputPaths.text_xls, outputPaths.text_xls, expectedPaths.text)
+})
+
+test('convert test for xlsb workbook of text', async (t) => {
+  await testConvert(t, inputPaths.text_xlsb, outputPaths.text_xlsb, expectedPaths.text)
+})
+
+test('error on corrupt xlsx keeps the xlsx error', async (t) => {
+  await t.throwsAsync(convertAsync({ inputPath: inputPaths.failCorrupt, outputPath: outputPaths.failOutput }), {
+    message: /^XlsxError/,
+  })
+})
+
+test('error on unsupported workbook format', async (t) => {
+  await t.throwsAsync(convertAsync({ inputPath: inputPaths.failUnsupportedFormat, outputPath: outputPaths.failOutput }))
+})
+
+test('error on existing output with noOverwrite', async (t) => {
+  await convertAsync({ inputPath: inputPaths.text, outputPath: outputPaths.text_no_overwrite })
+  await t.throwsAsync(
+    convertAsync({ inputPath: inputPaths.text, outputPath: outputPaths.text_no_overwrite, noOverwrite: true }),
+  )
+})
+
+test('error on default value out of number range', async (t) => {
+  await t.throwsAsync(convertAsync({ inputPath: inputPaths.failDefaultOutOfRange, outputPath: outputPaths.failOutput }))
+})
+
+test('convert test for group required setting on a later field', async (t) => {
+  await testConvert(t, inputPaths.panel_required_later, outputPaths.panel_required_later, expectedPaths.panel_required_later)
+})
+
+test('convert test for SurveyJS output of calculated', async (t) => {
+  await testConvert(t, inputPaths.calculated, outputPaths.calculated_surveyjs, expectedPaths.calculated_surveyjs, 'surveyjs')
+})
+
+test('convert test for visibility conditions', async (t) => {
+  await testConvert(t, inputPaths.visible_condition, outputPaths.visible_condition, expectedPaths.visible_condition)
+})
+
+test('convert test for SurveyJS output of visibility conditions', async (t) => {
+  await testConvert(t, inputPaths.visible_condition, outputPaths.visible_condition_surveyjs, expectedPaths.visible_condition_surveyjs, 'surveyjs')
+})
+
+test('convert test for rating html', async (t) => {
+  await testConvertText(t, inputPaths.rating, outputPaths.rating_html, expectedPaths.rating_html, 'html')
+})
+
 test('error on does not exist', async (t) => {
   try {
     await testConvert(t, inputPaths.failNotExist, outputPaths.failOutput, expectedPaths.radio)
@@ -77,14 +420,42 @@ const testConvert = async (
   inputPath: string,
   outputPath: string,
   expectedPath: string,
+  target?: OutputTarget,
 ) => {
-  await convertAsync({ inputPath, outputPath })
+  await convertAsync({ inputPath, outputPath, target })
   const expected = await readJson(expectedPath)
   const output = await readJson(outputPath)
   const isEq = _.isEqual(output, expected)
   t.is(isEq, true)
 }
 
+const testConvertText = async (
+  t: ExecutionContext<unknown>,
+  inputPath: string,
+  outputPath: string,
+  expectedPath: string,
+  target: OutputTarget,
+) => {
+  await convertAsync({ inputPath, outputPath, target })
+  const expected = await readFile(expectedPath)
+  const output = await readFile(outputPath)
+  t.is(output, expected)
+}
+
+const testConvertFormat = async (
+  t: ExecutionContext<unknown>,
+  inputPath: string,
+  outputPath: string,
+  expectedPath: string,
+  format: OutputFormat,
+  indent?: number,
+) => {
+  await convertAsync({ inputPath, outputPath, format, indent })
+  const expected = await readFile(expectedPath)
+  const output = await readFile(outputPath)
+  t.is(output, expected)
+}
+
 const readJson = async (path: string): Promise<any> => {
   try {
     const cont = await readFile(path)
@@ -1,6 +1,26 @@
 export const convertAsync: (args: ConvertArgs) => Promise<number>
 
+// Resolves with the problems in the spreadsheet that don't stop the conversion
+export const checkAsync: (args: CheckArgs) => Promise<string[]>
+
+export type OutputTarget = 'internal' | 'surveyjs' | 'jsonSchema' | 'typescript' | 'html' | 'markdown' | 'csv' | 'ir'
+
+// Encoding of the internal, surveyjs, jsonSchema and ir targets
+export type OutputFormat = 'json' | 'compactJson' | 'yaml'
+
 type ConvertArgs = {
   inputPath: string
   outputPath: string
+  target?: OutputTarget
+  format?: OutputFormat
+  // Spaces per level of json output, 2 when not set, rejected for other formats and text targets
+  indent?: number
+  // Fail instead of replacing an existing output file
+  noOverwrite?: boolean
+  // Create missing directories of the output path
+  createParentDirs?: boolean
+}
+
+type CheckArgs = {
+  inputPath: string
 }
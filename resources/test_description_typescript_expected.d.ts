@@ -0,0 +1,17 @@
+export interface Answers {
+  /**
+   * お名前をお知らせください。
+   *
+   * ご本人のお名前をご記入ください。
+   */
+  field1: string
+  /**
+   * ご来店の頻度をお知らせください。
+   *
+   * 直近1年間についてお答えください。
+   * 不明な場合は「わからない」を選択してください。
+   */
+  field2?: '月1回以上' | '年数回' | 'わからない'
+  /** ご意見をお聞かせください。 */
+  field3?: string
+}
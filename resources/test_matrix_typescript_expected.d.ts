@@ -0,0 +1,27 @@
+export interface Answers {
+  /** 各サービスの満足度をお知らせください。 */
+  field1: {
+    /** 接客 */
+    field1_1: '満足' | 'やや満足' | 'どちらでもない' | 'やや不満' | '不満'
+    /** 品揃え */
+    field1_2: '満足' | 'やや満足' | 'どちらでもない' | 'やや不満' | '不満'
+    /** 価格 */
+    field1_3: '満足' | 'やや満足' | 'どちらでもない' | 'やや不満' | '不満'
+  }
+  /** 各店舗の満足度をお知らせください。 */
+  field2?: {
+    /** 本店 */
+    field2_1?: '満足' | 'やや満足' | 'どちらでもない' | 'やや不満' | '不満'
+    /** 駅前店 */
+    field2_2?: '満足' | 'やや満足' | 'どちらでもない' | 'やや不満' | '不満'
+  }
+  /** ご意見をお聞かせください。 */
+  field3?: string
+  /** 各窓口の満足度をお知らせください。 */
+  field4: {
+    /** 電話 */
+    field4_1?: '満足' | 'やや満足' | 'どちらでもない' | 'やや不満' | '不満'
+    /** メール */
+    field4_2?: '満足' | 'やや満足' | 'どちらでもない' | 'やや不満' | '不満'
+  }
+}
@@ -0,0 +1,14 @@
+export interface Answers {
+  /** 好きな色を全てお知らせください。 */
+  field1: string[]
+  /** 好きな色を2つ以上お知らせください。 */
+  field2?: string[]
+  /** 好きな色を3つまでお知らせください。 */
+  field3: string[]
+  /** 好きな色を3つお知らせください。 */
+  field4: string[]
+  /** 好きな色を全てお知らせください。 */
+  field5: string[]
+  /** 好きな色を全てお知らせください。 */
+  field6: string[]
+}
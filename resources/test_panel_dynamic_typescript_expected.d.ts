@@ -0,0 +1,11 @@
+export interface Answers {
+  /** お名前をお知らせください。 */
+  field1: string
+  /** ご家族について */
+  panel1?: Array<{
+    /** ご家族のお名前をお知らせください。 */
+    field2: string
+    /** ご家族の年齢をお知らせください。 */
+    field3?: string
+  }>
+}
@@ -5,7 +5,7 @@ use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum ConvertError {
-  #[error("No worksheet in selected Xlsx file")]
+  #[error("No worksheet in selected workbook")]
   NoWorksheet,
   #[error("ReadError: {0}")]
   ReadError(String),
@@ -13,8 +13,12 @@ pub enum ConvertError {
   DeserializeError(String),
   #[error("XlsxError: {0}")]
   XlsxError(String),
+  #[error("\"{0}\" is not an xlsx, xls, xlsb or ods workbook")]
+  UnsupportedWorkbook(String),
   #[error("Wrong subject format \"{0}\"")]
   IncorrectSubject(String),
+  #[error("Wrong form setting \"{0}\"")]
+  IncorrectMetadataKey(String),
   #[error("Wrong field type \"{0}\"")]
   IncorrectFieldVariant(String),
   #[error("Wrong required format \"{0}\"")]
@@ -23,6 +27,14 @@ pub enum ConvertError {
   IncorrectInputSpecificationError(String),
   #[error("Wrong number input specification format \"{0}\"")]
   IncorrectNumInputSpecificationError(String),
+  #[error("Minimum length {0} is larger than maximum length {1}")]
+  IncorrectLengthRange(u64, u64),
+  #[error("Wrong group repetition format \"{0}\"")]
+  IncorrectPanelRepeat(String),
+  #[error("Wrong file type \"{0}\", expected a MIME type or an extension")]
+  IncorrectFileType(String),
+  #[error("Wrong file size format \"{0}\"")]
+  IncorrectFileSize(String),
   #[error("Expected string, found something else")]
   ExpectedString,
   #[error("Expected whole number, found something else")]
@@ -35,12 +47,53 @@ pub enum ConvertError {
   UnparseableCell,
   #[error("Placeholder for multiselect not in options")]
   PlaceholderNotInOptions,
+  #[error("Matrix has no rows")]
+  MatrixWithoutRows,
+  #[error("Rating minimum must be smaller than maximum")]
+  IncorrectRatingRange,
+  #[error("Ranking limit {0} is larger than the number of options")]
+  RankLimitExceedsOptions(u64),
+  #[error("Calculated field \"{0}\" has no calculation")]
+  MissingCalculation(String),
+  #[error("Wrong calculation format \"{0}\"")]
+  IncorrectCalculation(String),
+  #[error("Calculation of \"{0}\" refers to unknown field \"{1}\"")]
+  UnknownFieldReference(String, String),
+  #[error("Calculation of \"{0}\" depends on itself")]
+  CircularCalculation(String),
+  #[error("Default value is not supported for this field type")]
+  DefaultNotSupported,
+  #[error("Default value \"{0}\" is not a boolean")]
+  DefaultNotBoolean(String),
+  #[error("Default value \"{0}\" is not a number in the rating range")]
+  DefaultOutOfRange(String),
+  #[error("Default value \"{0}\" does not satisfy the input specification")]
+  DefaultViolatesInputSpec(String),
+  #[error("Default value \"{0}\" is not within the length limits")]
+  DefaultLengthOutOfRange(String),
+  #[error("Default value \"{0}\" not in options")]
+  DefaultNotInOptions(String),
+  #[error("Output target {0} can't be written as {1}")]
+  UnsupportedFormat(String, String),
+  #[error("Indent only applies to pretty json output, not {0}")]
+  UnsupportedIndent(String),
+  #[error("Expression \"{0}\" can't be written as a SurveyJS expression")]
+  UnsupportedExpression(String),
+  #[error("Output file \"{0}\" already exists")]
+  OutputExists(String),
   #[error("Error while trying to serialize: {0}")]
   SerializeError(String),
   #[error("IO Error: {0}")]
   IOError(String),
 }
 
+// Problems in the spreadsheet that don't stop the conversion
+#[derive(Error, Debug, PartialEq)]
+pub enum ConvertWarning {
+  #[error("Input specification \"{1}\" of \"{0}\" has no effect on a {2} field")]
+  IneffectiveInputSpec(String, String, String),
+}
+
 impl From<calamine::Error> for ConvertError {
   fn from(err: calamine::Error) -> Self {
     ConvertError::ReadError(err.to_string())
@@ -71,6 +124,12 @@ impl From<serde_json::Error> for ConvertError {
   }
 }
 
+impl From<serde_yaml_ng::Error> for ConvertError {
+  fn from(err: serde_yaml_ng::Error) -> Self {
+    ConvertError::SerializeError(err.to_string())
+  }
+}
+
 impl From<std::io::Error> for ConvertError {
   fn from(err: std::io::Error) -> Self {
     ConvertError::IOError(err.to_string())
@@ -0,0 +1,94 @@
+use super::subtypes::FieldVariant;
+use super::{Field, Page};
+use crate::converter::error::{ConvertError, Result};
+use std::collections::{HashMap, HashSet};
+
+impl Field {
+  // Field references may be written as "{field1}" or "${field1}", they are output as "${field1}"
+  pub(super) fn normalize_calculation(calc: &str) -> Result<String> {
+    let mut normalized = String::with_capacity(calc.len());
+    let mut rest = calc;
+    while let Some(start) = rest.find('{') {
+      let end = rest[start..]
+        .find('}')
+        .ok_or_else(|| ConvertError::IncorrectCalculation(calc.to_owned()))?;
+      let key = rest[start + 1..start + end].trim();
+      if key.is_empty() {
+        return Err(ConvertError::IncorrectCalculation(calc.to_owned()));
+      }
+      normalized.push_str(rest[..start].trim_end_matches('$'));
+      normalized.push_str(&format!("${{{}}}", key));
+      rest = &rest[start + end + 1..];
+    }
+    normalized.push_str(rest);
+    Ok(normalized)
+  }
+
+  pub(super) fn calculation_references(&self) -> Vec<String> {
+    let mut references = Vec::<String>::new();
+    if let Some(ref expr) = self.processed.expression {
+      for part in expr.split("${").skip(1) {
+        if let Some(end) = part.find('}') {
+          references.push(part[..end].trim_start_matches("panel.").to_owned());
+        }
+      }
+    }
+    references
+  }
+}
+
+impl Page {
+  pub fn check_calculations(&self) -> Result<()> {
+    let fields = self.fields();
+    let keys = fields
+      .iter()
+      .map(|f| f.field_name.as_str())
+      .collect::<HashSet<&str>>();
+
+    let mut dependencies = HashMap::<&str, Vec<String>>::new();
+    for field in fields.iter() {
+      if let FieldVariant::Calculated = field.variant {
+        let references = field.calculation_references();
+        for reference in references.iter() {
+          if !keys.contains(reference.as_str()) {
+            return Err(ConvertError::UnknownFieldReference(
+              field.field_name.to_owned(),
+              reference.to_owned(),
+            ));
+          }
+        }
+        dependencies.insert(&field.field_name, references);
+      }
+    }
+
+    // Depth first search over calculated fields, a key met again on the current path is a cycle
+    fn visit<'a>(
+      key: &'a str,
+      dependencies: &'a HashMap<&str, Vec<String>>,
+      path: &mut Vec<&'a str>,
+      done: &mut HashSet<&'a str>,
+    ) -> Result<()> {
+      if done.contains(key) {
+        return Ok(());
+      }
+      if path.contains(&key) {
+        return Err(ConvertError::CircularCalculation(key.to_owned()));
+      }
+      if let Some(references) = dependencies.get(key) {
+        path.push(key);
+        for reference in references.iter() {
+          visit(reference, dependencies, path, done)?;
+        }
+        path.pop();
+      }
+      done.insert(key);
+      Ok(())
+    }
+
+    let mut done = HashSet::<&str>::new();
+    for key in dependencies.keys() {
+      visit(key, &dependencies, &mut Vec::new(), &mut done)?;
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,256 @@
+use super::source::SourcePosition;
+use super::subtypes::RepeatSpec;
+use super::Field;
+use crate::converter::error::ConvertError;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
+pub(crate) enum Element {
+  Field(Field),
+  Panel(Panel),
+  Content(Content),
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub(crate) enum PanelVariant {
+  #[serde(rename = "panel")]
+  Panel,
+
+  #[serde(rename = "paneldynamic")]
+  Dynamic,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub(crate) struct Panel {
+  #[serde(rename = "Name")]
+  name: String,
+
+  #[serde(rename = "Required")]
+  is_required: bool,
+
+  #[serde(rename = "Type")]
+  variant: PanelVariant,
+
+  #[serde(rename = "Title")]
+  title: String,
+
+  #[serde(rename = "Visible", skip_serializing_if = "Option::is_none")]
+  visible: Option<String>,
+
+  #[serde(rename = "MinPanelCount", skip_serializing_if = "Option::is_none")]
+  min_count: Option<u64>,
+
+  #[serde(rename = "MaxPanelCount", skip_serializing_if = "Option::is_none")]
+  max_count: Option<u64>,
+
+  #[serde(rename = "Elements")]
+  elements: Vec<Element>,
+}
+
+impl Panel {
+  pub fn new(name: String, title: String) -> Self {
+    Panel {
+      name,
+      is_required: false,
+      variant: PanelVariant::Panel,
+      title,
+      visible: None,
+      min_count: None,
+      max_count: None,
+      elements: Vec::new(),
+    }
+  }
+
+  pub(crate) fn name(&self) -> &str {
+    &self.name
+  }
+
+  pub(crate) fn is_required(&self) -> bool {
+    self.is_required
+  }
+
+  pub(crate) fn variant(&self) -> &PanelVariant {
+    &self.variant
+  }
+
+  pub fn title(&self) -> &str {
+    &self.title
+  }
+
+  pub(crate) fn visible(&self) -> Option<&str> {
+    self.visible.as_deref()
+  }
+
+  pub(crate) fn min_count(&self) -> Option<u64> {
+    self.min_count
+  }
+
+  pub(crate) fn max_count(&self) -> Option<u64> {
+    self.max_count
+  }
+
+  pub(crate) fn elements(&self) -> &[Element] {
+    &self.elements
+  }
+
+  // Panel settings may be written on any field of the group, the first one found is used
+  pub fn merge_settings(
+    &mut self,
+    is_required: Option<bool>,
+    visible: Option<String>,
+    repeat: Option<RepeatSpec>,
+  ) {
+    if let Some(b) = is_required {
+      self.is_required |= b;
+    }
+    if self.visible.is_none() {
+      self.visible = visible;
+    }
+    if let (PanelVariant::Panel, Some(rpt)) = (&self.variant, repeat) {
+      self.variant = PanelVariant::Dynamic;
+      self.min_count = rpt.min;
+      self.max_count = rpt.max;
+    }
+  }
+
+  pub fn fields(&self) -> Vec<&Field> {
+    self
+      .elements
+      .iter()
+      .filter_map(|e| match e {
+        Element::Field(f) => Some(f),
+        _ => None,
+      })
+      .collect()
+  }
+
+  pub fn push(&mut self, element: Element) {
+    self.elements.push(element);
+  }
+
+  pub fn into_element(mut self) -> Element {
+    // The group is complete here, so the required setting reaches fields read before it too
+    if self.is_required {
+      for element in self.elements.iter_mut() {
+        if let Element::Field(field) = element {
+          field.set_required();
+        }
+      }
+    }
+    // Inside a dynamic panel, references to sibling fields point to the current panel item
+    if let PanelVariant::Dynamic = self.variant {
+      let keys = self
+        .fields()
+        .iter()
+        .map(|f| f.field_name().to_owned())
+        .collect::<Vec<String>>();
+      for element in self.elements.iter_mut() {
+        if let Element::Field(field) = element {
+          field.scope_to_panel(&keys);
+        }
+      }
+    }
+    Element::Panel(self)
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone)]
+pub(crate) enum ContentVariant {
+  Heading,
+  Text,
+}
+
+impl FromStr for ContentVariant {
+  type Err = ConvertError;
+
+  fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+    match s {
+      "見出し" => Ok(ContentVariant::Heading),
+      "説明文" => Ok(ContentVariant::Text),
+      unknown_string => Err(ConvertError::IncorrectFieldVariant(
+        unknown_string.to_owned(),
+      )),
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub(crate) enum ContentType {
+  #[serde(rename = "html")]
+  Html,
+}
+
+// Read-only block shown between questions, it has no answer so no Required or Validators
+#[derive(Serialize, Deserialize, Debug)]
+pub(crate) struct Content {
+  #[serde(rename = "Name")]
+  name: String,
+
+  #[serde(rename = "Type")]
+  content_type: ContentType,
+
+  #[serde(rename = "Html")]
+  html: String,
+
+  #[serde(rename = "Visible", skip_serializing_if = "Option::is_none")]
+  visible: Option<String>,
+
+  #[serde(skip)]
+  source: Option<SourcePosition>,
+}
+
+impl Content {
+  pub fn new(name: String, variant: ContentVariant, text: String, visible: Option<String>) -> Self {
+    let text = text.replace("\r\n", "\n");
+    let escaped = escape_html(&text).replace('\n', "<br>");
+    let html = match variant {
+      ContentVariant::Heading => format!("<h3>{}</h3>", escaped),
+      ContentVariant::Text => format!("<p>{}</p>", escaped),
+    };
+    Content {
+      name,
+      content_type: ContentType::Html,
+      html,
+      visible,
+      source: None,
+    }
+  }
+
+  pub(crate) fn name(&self) -> &str {
+    &self.name
+  }
+
+  pub(crate) fn html(&self) -> &str {
+    &self.html
+  }
+
+  pub(crate) fn visible(&self) -> Option<&str> {
+    self.visible.as_deref()
+  }
+
+  pub(crate) fn source(&self) -> Option<&SourcePosition> {
+    self.source.as_ref()
+  }
+
+  pub(crate) fn set_source(&mut self, source: SourcePosition) {
+    self.source = Some(source);
+  }
+}
+
+pub(crate) fn escape_html(s: &str) -> String {
+  let mut escaped = String::with_capacity(s.len());
+  for c in s.chars() {
+    match c {
+      '&' => escaped.push_str("&amp;"),
+      '<' => escaped.push_str("&lt;"),
+      '>' => escaped.push_str("&gt;"),
+      '"' => escaped.push_str("&quot;"),
+      '\'' => escaped.push_str("&#39;"),
+      c => escaped.push(c),
+    }
+  }
+  escaped
+}
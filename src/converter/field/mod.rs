@@ -1,15 +1,25 @@
-use super::error::{ConvertError, Result};
+use super::error::{ConvertError, ConvertWarning, Result};
+use super::metadata::FormMetadata;
 use calamine::DataType;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
+mod calculation;
+pub mod element;
+pub mod source;
 pub mod subtypes;
 mod validators;
+use element::Element;
+use source::SourcePosition;
 use subtypes::*;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub(crate) struct Page {
+  #[serde(flatten)]
+  metadata: FormMetadata,
+
   #[serde(rename = "Elements")]
-  elements: Vec<Field>,
+  elements: Vec<Element>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -26,6 +36,9 @@ pub(crate) struct Field {
   #[serde(rename = "Label")]
   label: String,
 
+  #[serde(rename = "Description", skip_serializing_if = "Option::is_none")]
+  description: Option<String>,
+
   #[serde(skip)]
   placeholder_text: Option<String>,
 
@@ -47,12 +60,51 @@ pub(crate) struct Field {
   #[serde(rename = "OptionsFromKey", skip_serializing_if = "Option::is_none")]
   options_from_key: Option<String>,
 
+  #[serde(rename = "Rows", skip_serializing_if = "Option::is_none")]
+  matrix_rows: Option<Vec<OptionType>>,
+
+  #[serde(skip)]
+  all_rows_required: Option<bool>,
+
+  #[serde(skip)]
+  default_value: Option<String>,
+
   #[serde(skip)]
   max: Option<u64>,
 
   #[serde(skip)]
   min: Option<u64>,
 
+  #[serde(skip)]
+  max_chars: Option<u64>,
+
+  #[serde(skip)]
+  step: Option<u64>,
+
+  #[serde(skip)]
+  max_label: Option<String>,
+
+  #[serde(skip)]
+  min_label: Option<String>,
+
+  #[serde(skip)]
+  true_label: Option<String>,
+
+  #[serde(skip)]
+  false_label: Option<String>,
+
+  #[serde(skip)]
+  calculation: Option<String>,
+
+  #[serde(skip)]
+  visible_condition: Option<String>,
+
+  #[serde(skip)]
+  file_types: Option<Vec<String>>,
+
+  #[serde(skip)]
+  max_file_size: Option<u64>,
+
   // #[serde(skip_serializing_if = "Option::is_none")]
   #[serde(skip)]
   display_condition_first: Option<Vec<String>>,
@@ -67,11 +119,39 @@ pub(crate) struct Field {
 
   #[serde(flatten)]
   processed: ProcessedData,
+
+  #[serde(skip)]
+  source: Option<SourcePosition>,
 }
 
 impl Page {
-  pub fn new(elements: Vec<Field>) -> Self {
-    Page { elements }
+  pub fn new(metadata: FormMetadata, elements: Vec<Element>) -> Self {
+    Page { metadata, elements }
+  }
+
+  pub(crate) fn metadata(&self) -> &FormMetadata {
+    &self.metadata
+  }
+
+  pub(crate) fn elements(&self) -> &[Element] {
+    &self.elements
+  }
+
+  // All fields of the page in order, including the ones inside panels
+  pub fn fields(&self) -> Vec<&Field> {
+    let mut fields = Vec::<&Field>::new();
+    for element in self.elements.iter() {
+      match element {
+        Element::Field(f) => fields.push(f),
+        Element::Panel(p) => fields.extend(p.fields()),
+        Element::Content(_) => (),
+      }
+    }
+    fields
+  }
+
+  pub fn warnings(&self) -> Vec<ConvertWarning> {
+    self.fields().iter().flat_map(|f| f.warnings()).collect()
   }
 }
 
@@ -81,18 +161,38 @@ impl Field {
     field_name: String,
     variant: FieldVariant,
     label: String,
+    description: Option<String>,
     placeholder_text: Option<String>,
     input_spec: Option<InputSpec>,
     num_input_spec: Option<NumInputSpec>,
     num_input_spec_error: Option<String>,
     options: Option<Vec<OptionType>>,
     options_from_key: Option<String>,
+    matrix_rows: Option<Vec<OptionType>>,
+    all_rows_required: Option<bool>,
+    default_value: Option<String>,
     max: Option<u64>,
     min: Option<u64>,
+    max_chars: Option<u64>,
+    step: Option<u64>,
+    max_label: Option<String>,
+    min_label: Option<String>,
+    true_label: Option<String>,
+    false_label: Option<String>,
+    calculation: Option<String>,
+    visible_condition: Option<String>,
+    file_types: Option<Vec<String>>,
+    max_file_size: Option<u64>,
     display_condition_first: Option<Vec<String>>,
     display_condition_second: Option<Vec<String>>,
     display_condition_third: Option<Vec<String>>,
   ) -> Result<Self> {
+    // Hidden and calculated fields are never shown, so they can't be required
+    let is_required = match variant {
+      FieldVariant::Hidden | FieldVariant::Calculated => false,
+      _ => is_required,
+    };
+
     // Placeholder logic
     let mut placeholder: Option<String> = None;
     let mut options_key: Option<String> = None;
@@ -112,6 +212,14 @@ impl Field {
       price_max = max;
     }
 
+    // MaxLength logic, 最大 of a textarea is its PriceMax so its length comes from 最大文字数 only
+    let mut max_length: Option<u64> = None;
+    match variant {
+      FieldVariant::Text => max_length = max_chars.or(max),
+      FieldVariant::TextArea => max_length = max_chars,
+      _ => (),
+    }
+
     // Validators logic
     let mut validators: Option<Vec<Validator>>;
     match variant {
@@ -119,14 +227,23 @@ impl Field {
         validators = Some(Self::text_validators(
           &field_name,
           &min,
-          &max,
+          &max_length,
           &input_spec,
           &num_input_spec,
           &num_input_spec_error,
-        ));
+        )?);
       }
       FieldVariant::TextArea => {
-        validators = Some(Self::textarea_validators(&min));
+        validators = Some(Self::textarea_validators(&min, &max_length)?);
+      }
+      FieldVariant::Boolean => {
+        validators = Some(Self::boolean_validators(&field_name, is_required));
+      }
+      FieldVariant::File => {
+        validators = Some(Self::file_validators(&min, &max));
+      }
+      FieldVariant::Ranking => {
+        validators = Some(Self::ranking_validators(&max, &options)?);
       }
       FieldVariant::Multiselect => {
         validators = Some(Self::multiselect_validators(
@@ -147,10 +264,90 @@ impl Field {
       }
     }
 
-    // Visibility logic
-    let mut visible: Option<String> = None;
+    // Matrix logic, a required matrix needs one answered row unless every row is required
+    let mut all_rows: Option<bool> = None;
+    if let FieldVariant::Matrix = variant {
+      if matrix_rows.is_none() {
+        return Err(ConvertError::MatrixWithoutRows);
+      }
+      if let Some(true) = all_rows_required {
+        all_rows = Some(true);
+      }
+    }
+
+    // Rating logic
+    let mut rating: Option<RatingSpec> = None;
+    if let FieldVariant::Rating = variant {
+      if let (Some(mn), Some(mx)) = (min, max) {
+        if mn >= mx {
+          return Err(ConvertError::IncorrectRatingRange);
+        }
+      }
+      // Without a maximum the scale keeps the usual five points from wherever it starts
+      rating = Some(RatingSpec {
+        min,
+        max: max.or_else(|| min.map(|mn| mn + 4)),
+        step,
+        min_label: min_label.to_owned(),
+        max_label: max_label.to_owned(),
+      });
+    }
+
+    // Default value logic
+    let processed_default = Self::processed_default_value(
+      &variant,
+      &default_value,
+      &options,
+      &options_from_key,
+      &matrix_rows,
+      &rating,
+      &input_spec,
+      &num_input_spec,
+      &min,
+      &max_length,
+    )?;
+
+    // Boolean logic
+    let mut label_true: Option<String> = None;
+    let mut label_false: Option<String> = None;
+    if let FieldVariant::Boolean = variant {
+      label_true = true_label.to_owned();
+      label_false = false_label.to_owned();
+    }
+
+    // File logic
+    let mut file: Option<FileSpec> = None;
+    if let FieldVariant::File = variant {
+      file = Some(FileSpec {
+        accepted_types: file_types.as_ref().map(|types| types.join(",")),
+        max_size: max_file_size,
+        allow_multiple: !matches!(max, Some(mx) if mx <= 1),
+      });
+    }
+
+    // Ranking logic, only the top N options are ranked when a limit is set
+    let mut select_to_rank: Option<bool> = None;
+    if let (FieldVariant::Ranking, Some(_)) = (&variant, max) {
+      select_to_rank = Some(true);
+    }
+
+    // Calculation logic
+    let mut expression: Option<String> = None;
+    if let FieldVariant::Calculated = variant {
+      match calculation {
+        Some(ref calc) => expression = Some(Self::normalize_calculation(calc)?),
+        None => return Err(ConvertError::MissingCalculation(field_name)),
+      }
+    }
+
+    // Visibility logic, a field with options from another field also waits for them
+    let mut visible: Option<String> = visible_condition.to_owned();
     if let Some(ref opt_from_key) = options_from_key {
-      visible = Some(format!("${{{0}}} && ${{{0}}}.length > 1", opt_from_key));
+      let has_options = format!("${{{0}}} && ${{{0}}}.length > 1", opt_from_key);
+      visible = Some(match visible {
+        Some(cond) => format!("({}) && ({})", cond, has_options),
+        None => has_options,
+      });
     }
 
     Ok(Field {
@@ -158,14 +355,28 @@ impl Field {
       field_name,
       variant,
       label,
+      description,
       placeholder_text,
       input_spec,
       num_input_spec,
       num_input_spec_error,
       options,
       options_from_key,
+      matrix_rows,
+      all_rows_required,
+      default_value,
       max,
       min,
+      max_chars,
+      step,
+      max_label,
+      min_label,
+      true_label,
+      false_label,
+      calculation,
+      visible_condition,
+      file_types,
+      max_file_size,
       display_condition_first,
       display_condition_second,
       display_condition_third,
@@ -175,10 +386,321 @@ impl Field {
         placeholder,
         options_caption: options_key,
         visible,
+        default_value: processed_default,
+        all_rows_required: all_rows,
+        rating,
+        label_true,
+        label_false,
+        file,
+        select_to_rank,
+        expression,
+        max_length,
       },
+      source: None,
     })
   }
 
+  fn processed_default_value(
+    variant: &FieldVariant,
+    default_value: &Option<String>,
+    options: &Option<Vec<OptionType>>,
+    options_from_key: &Option<String>,
+    matrix_rows: &Option<Vec<OptionType>>,
+    rating: &Option<RatingSpec>,
+    input_spec: &Option<InputSpec>,
+    num_input_spec: &Option<NumInputSpec>,
+    min_length: &Option<u64>,
+    max_length: &Option<u64>,
+  ) -> Result<Option<Value>> {
+    let default_text = match default_value {
+      Some(d) => d,
+      None => return Ok(None),
+    };
+    // Options are only known at runtime when they come from another field
+    let check_in_options = |v: &str| -> Result<()> {
+      if options_from_key.is_some() {
+        return Ok(());
+      }
+      match options {
+        Some(opts) if opts.iter().any(|o| o.is_val(v)) => Ok(()),
+        _ => Err(ConvertError::DefaultNotInOptions(v.to_owned())),
+      }
+    };
+    match variant {
+      // Text defaults must fit the length limits, only single line text has input specifications
+      FieldVariant::Text | FieldVariant::TextArea => {
+        let length = default_text.chars().count() as u64;
+        let too_short = matches!(min_length, Some(mn) if length < *mn);
+        let too_long = matches!(max_length, Some(mx) if length > *mx);
+        if too_short || too_long {
+          return Err(ConvertError::DefaultLengthOutOfRange(
+            default_text.to_owned(),
+          ));
+        }
+        if let FieldVariant::Text = variant {
+          let matches_input_spec = match input_spec {
+            Some(spec) => spec.accepts(default_text),
+            None => true,
+          };
+          let in_num_range = match num_input_spec {
+            Some(spec) => default_text.parse::<f64>().is_ok_and(|n| spec.contains(n)),
+            None => true,
+          };
+          if !matches_input_spec || !in_num_range {
+            return Err(ConvertError::DefaultViolatesInputSpec(
+              default_text.to_owned(),
+            ));
+          }
+        }
+        Ok(Some(Value::String(default_text.to_owned())))
+      }
+      FieldVariant::Hidden => Ok(Some(Value::String(default_text.to_owned()))),
+      FieldVariant::Dropdown | FieldVariant::Radio => {
+        check_in_options(default_text)?;
+        Ok(Some(Value::String(default_text.to_owned())))
+      }
+      FieldVariant::Multiselect | FieldVariant::Ranking => {
+        // Options are one per line or separated by commas, an option containing a comma can be
+        // given on its own or on a line of its own
+        let values: Vec<&str> = if default_text.contains('\n') {
+          default_text.lines().collect()
+        } else if options_from_key.is_none() && check_in_options(default_text).is_ok() {
+          vec![default_text]
+        } else {
+          default_text.split(&[',', '、', '，'][..]).collect()
+        };
+        let mut selected = Vec::<Value>::new();
+        for v in values
+          .into_iter()
+          .map(|s| s.trim())
+          .filter(|s| !s.is_empty())
+        {
+          check_in_options(v)?;
+          selected.push(Value::String(v.to_owned()));
+        }
+        Ok(Some(Value::Array(selected)))
+      }
+      // The default column is pre-selected on every row
+      FieldVariant::Matrix => {
+        check_in_options(default_text)?;
+        let mut selected = serde_json::Map::new();
+        for row in matrix_rows.iter().flatten() {
+          selected.insert(
+            row.value().to_owned(),
+            Value::String(default_text.to_owned()),
+          );
+        }
+        Ok(Some(Value::Object(selected)))
+      }
+      FieldVariant::Rating => {
+        let rate = default_text
+          .parse::<u64>()
+          .map_err(|_| ConvertError::DefaultOutOfRange(default_text.to_owned()))?;
+        if let Some(spec) = rating {
+          let below_min = matches!(spec.min, Some(mn) if rate < mn);
+          let above_max = matches!(spec.max, Some(mx) if rate > mx);
+          if below_min || above_max {
+            return Err(ConvertError::DefaultOutOfRange(default_text.to_owned()));
+          }
+        }
+        Ok(Some(Value::from(rate)))
+      }
+      FieldVariant::Boolean => match default_text.to_lowercase().as_str() {
+        "true" => Ok(Some(Value::Bool(true))),
+        "false" => Ok(Some(Value::Bool(false))),
+        _ => Err(ConvertError::DefaultNotBoolean(default_text.to_owned())),
+      },
+      FieldVariant::File | FieldVariant::Calculated => Err(ConvertError::DefaultNotSupported),
+    }
+  }
+
+  pub fn field_name(&self) -> &str {
+    &self.field_name
+  }
+
+  pub(crate) fn is_required(&self) -> bool {
+    self.is_required
+  }
+
+  pub(crate) fn variant(&self) -> &FieldVariant {
+    &self.variant
+  }
+
+  pub(crate) fn label(&self) -> &str {
+    &self.label
+  }
+
+  pub(crate) fn description(&self) -> Option<&str> {
+    self.description.as_deref()
+  }
+
+  pub(crate) fn placeholder_text(&self) -> Option<&str> {
+    self.placeholder_text.as_deref()
+  }
+
+  pub(crate) fn input_spec(&self) -> Option<&InputSpec> {
+    self.input_spec.as_ref()
+  }
+
+  pub(crate) fn num_input_spec(&self) -> Option<&NumInputSpec> {
+    self.num_input_spec.as_ref()
+  }
+
+  pub(crate) fn num_input_spec_error(&self) -> Option<&str> {
+    self.num_input_spec_error.as_deref()
+  }
+
+  pub(crate) fn options(&self) -> Option<&[OptionType]> {
+    self.options.as_deref()
+  }
+
+  pub(crate) fn options_from_key(&self) -> Option<&str> {
+    self.options_from_key.as_deref()
+  }
+
+  pub(crate) fn matrix_rows(&self) -> Option<&[OptionType]> {
+    self.matrix_rows.as_deref()
+  }
+
+  pub(crate) fn all_rows_required(&self) -> Option<bool> {
+    self.all_rows_required
+  }
+
+  pub(crate) fn default_value(&self) -> Option<&str> {
+    self.default_value.as_deref()
+  }
+
+  pub(crate) fn max(&self) -> Option<u64> {
+    self.max
+  }
+
+  pub(crate) fn min(&self) -> Option<u64> {
+    self.min
+  }
+
+  pub(crate) fn max_chars(&self) -> Option<u64> {
+    self.max_chars
+  }
+
+  pub(crate) fn step(&self) -> Option<u64> {
+    self.step
+  }
+
+  pub(crate) fn max_label(&self) -> Option<&str> {
+    self.max_label.as_deref()
+  }
+
+  pub(crate) fn min_label(&self) -> Option<&str> {
+    self.min_label.as_deref()
+  }
+
+  pub(crate) fn true_label(&self) -> Option<&str> {
+    self.true_label.as_deref()
+  }
+
+  pub(crate) fn false_label(&self) -> Option<&str> {
+    self.false_label.as_deref()
+  }
+
+  pub(crate) fn calculation(&self) -> Option<&str> {
+    self.calculation.as_deref()
+  }
+
+  pub(crate) fn visible_condition(&self) -> Option<&str> {
+    self.visible_condition.as_deref()
+  }
+
+  pub(crate) fn file_types(&self) -> Option<&[String]> {
+    self.file_types.as_deref()
+  }
+
+  pub(crate) fn max_file_size(&self) -> Option<u64> {
+    self.max_file_size
+  }
+
+  pub(crate) fn display_condition_first(&self) -> Option<&[String]> {
+    self.display_condition_first.as_deref()
+  }
+
+  pub(crate) fn display_condition_second(&self) -> Option<&[String]> {
+    self.display_condition_second.as_deref()
+  }
+
+  pub(crate) fn display_condition_third(&self) -> Option<&[String]> {
+    self.display_condition_third.as_deref()
+  }
+
+  pub(crate) fn processed(&self) -> &ProcessedData {
+    &self.processed
+  }
+
+  pub(crate) fn source(&self) -> Option<&SourcePosition> {
+    self.source.as_ref()
+  }
+
+  pub(crate) fn set_source(&mut self, source: SourcePosition) {
+    self.source = Some(source);
+  }
+
+  // Input specifications only produce validators for single line text fields
+  pub fn warnings(&self) -> Vec<ConvertWarning> {
+    let mut warnings = Vec::<ConvertWarning>::new();
+    if let FieldVariant::Text = self.variant {
+      return warnings;
+    }
+    if self.input_spec.is_some() {
+      warnings.push(ConvertWarning::IneffectiveInputSpec(
+        self.field_name.to_owned(),
+        "入力指定".to_owned(),
+        format!("{:?}", self.variant),
+      ));
+    }
+    if self.num_input_spec.is_some() {
+      warnings.push(ConvertWarning::IneffectiveInputSpec(
+        self.field_name.to_owned(),
+        "数字入力指定範囲".to_owned(),
+        format!("{:?}", self.variant),
+      ));
+    }
+    warnings
+  }
+
+  // Requiring a field after it is built also requires what depends on it
+  pub fn set_required(&mut self) {
+    if self.is_required
+      || matches!(
+        self.variant,
+        FieldVariant::Hidden | FieldVariant::Calculated
+      )
+    {
+      return;
+    }
+    self.is_required = true;
+    if let FieldVariant::Boolean = self.variant {
+      self.processed.validators = Some(Self::boolean_validators(&self.field_name, true));
+    }
+  }
+
+  pub fn scope_to_panel(&mut self, keys: &[String]) {
+    let scope = |expr: &str| -> String {
+      keys.iter().fold(expr.to_owned(), |acc, key| {
+        acc.replace(&format!("${{{}}}", key), &format!("${{panel.{}}}", key))
+      })
+    };
+    if let Some(ref mut vlds) = self.processed.validators {
+      for vld in vlds.iter_mut() {
+        vld.expression = vld.expression.as_deref().map(scope);
+      }
+    }
+    self.processed.visible = self.processed.visible.as_deref().map(scope);
+    self.processed.expression = self.processed.expression.as_deref().map(scope);
+    if let Some(ref opt_from_key) = self.options_from_key {
+      if keys.contains(opt_from_key) {
+        self.options_from_key = Some(format!("panel.{}", opt_from_key));
+      }
+    }
+  }
+
   pub fn field_number_from_datatype(dt: &DataType) -> Result<usize> {
     match dt {
       DataType::String(s) => Ok(
@@ -236,6 +758,47 @@ impl Field {
     }
   }
 
+  pub fn repeat_specification_from_datatype(dt: &DataType) -> Result<Option<RepeatSpec>> {
+    match dt {
+      DataType::Empty => Ok(None),
+      DataType::String(s) if s.is_empty() => Ok(None),
+      DataType::String(s) => Ok(Some(s.parse::<RepeatSpec>()?)),
+      _ => Err(ConvertError::ExpectedString),
+    }
+  }
+
+  pub fn file_types_from_datatype(dt: &DataType) -> Result<Option<Vec<String>>> {
+    let types = match Self::optional_string_from_datatype(dt)? {
+      Some(s) => s,
+      None => return Ok(None),
+    };
+    let mut file_types = Vec::<String>::new();
+    for t in types.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+      // MIME type like "image/png" or "image/*", or an extension like ".pdf"
+      let is_mime = match t.split('/').collect::<Vec<&str>>().as_slice() {
+        [tp, sub] => !tp.is_empty() && !sub.is_empty(),
+        _ => false,
+      };
+      let is_extension = t.starts_with('.') && t.len() > 1;
+      if !is_mime && !is_extension {
+        return Err(ConvertError::IncorrectFileType(t.to_owned()));
+      }
+      file_types.push(t.to_owned());
+    }
+    Ok(Self::vec_to_optional_vec(file_types))
+  }
+
+  pub fn file_size_from_datatype(dt: &DataType) -> Result<Option<u64>> {
+    match dt {
+      DataType::Empty => Ok(None),
+      DataType::String(s) if s.is_empty() => Ok(None),
+      DataType::String(s) => Ok(Some(s.parse::<FileSize>()?.0)),
+      DataType::Float(f) => Ok(Some(*f as u64)),
+      DataType::Int(i) => Ok(Some(*i as u64)),
+      _ => Err(ConvertError::UnparseableCell),
+    }
+  }
+
   pub fn optional_string_from_datatype(dt: &DataType) -> Result<Option<String>> {
     match dt {
       DataType::Empty => Ok(None),
@@ -244,10 +807,18 @@ impl Field {
       DataType::Float(f) => Ok(Some(format!("{}", f))),
       DataType::Int(i) => Ok(Some(format!("{}", i))),
       DataType::DateTime(f) => Ok(Some(format!("{}", f))),
+      DataType::Bool(b) => Ok(Some(format!("{}", b))),
       _ => Err(ConvertError::UnparseableCell),
     }
   }
 
+  pub fn multiline_string_from_datatype(dt: &DataType) -> Result<Option<String>> {
+    // Multi-line cells may carry Windows line breaks
+    Ok(
+      Self::optional_string_from_datatype(dt)?.map(|s| s.replace("\r\n", "\n").replace('\r', "\n")),
+    )
+  }
+
   pub fn optional_u64_from_datatype(dt: &DataType) -> Result<Option<u64>> {
     match dt {
       DataType::Empty => Ok(None),
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+// Where a field was read from in the workbook, for troubleshooting spreadsheets
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub(crate) struct SourcePosition {
+  #[serde(rename = "Sheet")]
+  pub sheet: String,
+
+  #[serde(rename = "Column")]
+  pub column: String,
+
+  #[serde(rename = "Cells")]
+  pub cells: Vec<SourceCell>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct SourceCell {
+  #[serde(rename = "Subject")]
+  pub subject: String,
+
+  #[serde(rename = "Cell")]
+  pub cell: String,
+
+  #[serde(rename = "Value")]
+  pub value: String,
+}
+
+impl SourcePosition {
+  pub fn new(sheet: &str, column: u32) -> Self {
+    SourcePosition {
+      sheet: sheet.to_owned(),
+      column: column_name(column),
+      cells: Vec::new(),
+    }
+  }
+
+  pub fn push(&mut self, subject: &str, row: u32, column: u32, value: String) {
+    self.cells.push(SourceCell {
+      subject: subject.to_owned(),
+      cell: format!("{}{}", column_name(column), row + 1),
+      value,
+    });
+  }
+}
+
+// Zero based column index to its spreadsheet name, 0 is "A" and 26 is "AA"
+pub(crate) fn column_name(column: u32) -> String {
+  let mut name = Vec::<char>::new();
+  let mut n = column + 1;
+  while n > 0 {
+    let rem = (n - 1) % 26;
+    name.push((b'A' + rem as u8) as char);
+    n = (n - 1) / 26;
+  }
+  name.iter().rev().collect()
+}
@@ -1,5 +1,7 @@
 use crate::converter::error::ConvertError;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
 use std::str::FromStr;
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -18,6 +20,27 @@ pub(crate) enum FieldVariant {
 
   #[serde(rename = "checkbox")]
   Multiselect,
+
+  #[serde(rename = "matrix")]
+  Matrix,
+
+  #[serde(rename = "rating")]
+  Rating,
+
+  #[serde(rename = "boolean")]
+  Boolean,
+
+  #[serde(rename = "file")]
+  File,
+
+  #[serde(rename = "ranking")]
+  Ranking,
+
+  #[serde(rename = "hidden")]
+  Hidden,
+
+  #[serde(rename = "expression")]
+  Calculated,
 }
 
 impl Default for FieldVariant {
@@ -36,6 +59,13 @@ impl FromStr for FieldVariant {
       "テキストエリア" => Ok(FieldVariant::TextArea),
       "マルチセレクト" => Ok(FieldVariant::Multiselect),
       "ラジオボタン" => Ok(FieldVariant::Radio),
+      "マトリクス" => Ok(FieldVariant::Matrix),
+      "評価" => Ok(FieldVariant::Rating),
+      "同意" => Ok(FieldVariant::Boolean),
+      "ファイル" => Ok(FieldVariant::File),
+      "ランキング" => Ok(FieldVariant::Ranking),
+      "非表示" => Ok(FieldVariant::Hidden),
+      "計算" => Ok(FieldVariant::Calculated),
       unknown_string => Err(ConvertError::IncorrectFieldVariant(
         unknown_string.to_owned(),
       )),
@@ -46,10 +76,10 @@ impl FromStr for FieldVariant {
 #[derive(Serialize, Deserialize, Debug)]
 pub(crate) struct OptionType {
   #[serde(rename = "Value")]
-  value: String,
+  pub value: String,
 
   #[serde(rename = "Label")]
-  label: String,
+  pub label: String,
 }
 
 impl OptionType {
@@ -60,6 +90,14 @@ impl OptionType {
     }
   }
 
+  pub fn with_label(value: String, label: String) -> Self {
+    OptionType { value, label }
+  }
+
+  pub fn value(&self) -> &str {
+    &self.value
+  }
+
   pub fn is_val(&self, v: &str) -> bool {
     self.value == v
   }
@@ -85,33 +123,248 @@ impl FromStr for InputSpec {
   }
 }
 
-#[derive(Serialize, Deserialize, Debug, Copy, Clone)]
+impl InputSpec {
+  pub fn accepts(&self, text: &str) -> bool {
+    match self {
+      InputSpec::HalfWidthNumber => !text.is_empty() && text.chars().all(|c| c.is_ascii_digit()),
+      InputSpec::HalfWidthKanji => {
+        !text.is_empty() && text.chars().all(|c| c.is_ascii_alphabetic())
+      }
+    }
+  }
+}
+
+impl fmt::Display for InputSpec {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      InputSpec::HalfWidthNumber => write!(f, "半角数字"),
+      InputSpec::HalfWidthKanji => write!(f, "半角英字"),
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub(crate) struct NumBound {
+  #[serde(rename = "Value")]
+  pub value: f64,
+
+  #[serde(rename = "Inclusive")]
+  pub inclusive: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
 pub(crate) struct NumInputSpec {
-  pub max: u32,
-  pub min: u32,
+  #[serde(rename = "Max")]
+  pub max: Option<NumBound>,
+
+  #[serde(rename = "Min")]
+  pub min: Option<NumBound>,
+}
+
+impl NumInputSpec {
+  pub fn contains(&self, value: f64) -> bool {
+    let above_min = match self.min {
+      Some(mn) if mn.inclusive => value >= mn.value,
+      Some(mn) => value > mn.value,
+      None => true,
+    };
+    let below_max = match self.max {
+      Some(mx) if mx.inclusive => value <= mx.value,
+      Some(mx) => value < mx.value,
+      None => true,
+    };
+    above_min && below_max
+  }
+
+  pub fn expression(&self, field_name: &str) -> String {
+    let mut expression = format!("${{{0}}}", field_name);
+    if let Some(mn) = self.min {
+      let op = if mn.inclusive { ">=" } else { ">" };
+      expression.push_str(&format!(" && ${{{0}}} {1} {2}", field_name, op, mn.value));
+    }
+    if let Some(mx) = self.max {
+      let op = if mx.inclusive { "<=" } else { "<" };
+      expression.push_str(&format!(" && ${{{0}}} {1} {2}", field_name, op, mx.value));
+    }
+    expression
+  }
+
+  pub fn error_text(&self) -> String {
+    let mut text = String::new();
+    if let Some(mn) = self.min {
+      let word = if mn.inclusive { "以上" } else { "超" };
+      text.push_str(&format!("{}{}", mn.value, word));
+    }
+    if let Some(mx) = self.max {
+      let word = if mx.inclusive { "以下" } else { "未満" };
+      text.push_str(&format!("{}{}", mx.value, word));
+    }
+    format!("{}の数字で入力してください", text)
+  }
+}
+
+// Written back in the grammar it is parsed from, brackets only for exclusive bounds
+impl fmt::Display for NumInputSpec {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    if let Some(mn) = self.min {
+      let open = if mn.inclusive { "" } else { "(" };
+      write!(f, "{}{}", open, mn.value)?;
+    }
+    write!(f, "~")?;
+    if let Some(mx) = self.max {
+      let close = if mx.inclusive { "" } else { ")" };
+      write!(f, "{}{}", mx.value, close)?;
+    }
+    Ok(())
+  }
 }
 
+// Grammar: [min~max], brackets are optional and default to inclusive bounds,
+// "(" or ")" make a bound exclusive, either bound may be left out (e.g. "~90", "10~")
 impl FromStr for NumInputSpec {
   type Err = ConvertError;
 
   fn from_str(s: &str) -> Result<Self, Self::Err> {
-    match s.split('~').collect::<Vec<&str>>().as_slice() {
+    let err = || ConvertError::IncorrectNumInputSpecificationError(s.to_owned());
+    let mut spec = s.trim();
+    let mut min_inclusive = true;
+    let mut max_inclusive = true;
+    if let Some(rest) = spec.strip_prefix('(') {
+      min_inclusive = false;
+      spec = rest;
+    } else if let Some(rest) = spec.strip_prefix('[') {
+      spec = rest;
+    }
+    if let Some(rest) = spec.strip_suffix(')') {
+      max_inclusive = false;
+      spec = rest;
+    } else if let Some(rest) = spec.strip_suffix(']') {
+      spec = rest;
+    }
+
+    let parse_bound = |b: &str, inclusive: bool| -> Result<Option<NumBound>, Self::Err> {
+      match b.trim() {
+        "" => Ok(None),
+        value => match value.parse::<f64>() {
+          Ok(v) if v.is_finite() => Ok(Some(NumBound {
+            value: v,
+            inclusive,
+          })),
+          _ => Err(err()),
+        },
+      }
+    };
+    match spec
+      .split(&['~', '〜', '～'][..])
+      .collect::<Vec<&str>>()
+      .as_slice()
+    {
       [mn, mx] => {
-        if let (Ok(min), Ok(max)) = (mn.parse::<u32>(), mx.parse::<u32>()) {
-          Ok(Self { min, max })
-        } else {
-          Err(ConvertError::IncorrectNumInputSpecificationError(
-            s.to_owned(),
-          ))
+        let min = parse_bound(mn, min_inclusive)?;
+        let max = parse_bound(mx, max_inclusive)?;
+        match (min, max) {
+          (None, None) => Err(err()),
+          (Some(mn), Some(mx)) if mn.value > mx.value => Err(err()),
+          (Some(mn), Some(mx)) if mn.value == mx.value && !(mn.inclusive && mx.inclusive) => {
+            Err(err())
+          }
+          _ => Ok(Self { min, max }),
         }
       }
-      _ => Err(ConvertError::IncorrectNumInputSpecificationError(
-        s.to_owned(),
-      )),
+      _ => Err(err()),
     }
   }
 }
 
+#[derive(Serialize, Deserialize, Debug, Copy, Clone)]
+pub(crate) struct RepeatSpec {
+  pub max: Option<u64>,
+  pub min: Option<u64>,
+}
+
+impl FromStr for RepeatSpec {
+  type Err = ConvertError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let parse_count = |c: &str| -> Result<Option<u64>, Self::Err> {
+      match c.trim() {
+        "" => Ok(None),
+        count => count
+          .parse::<u64>()
+          .map(Some)
+          .map_err(|_| ConvertError::IncorrectPanelRepeat(s.to_owned())),
+      }
+    };
+    match s
+      .split(&['~', '〜', '～'][..])
+      .collect::<Vec<&str>>()
+      .as_slice()
+    {
+      [mn, mx] => match (parse_count(mn)?, parse_count(mx)?) {
+        (Some(min), Some(max)) if min > max => {
+          Err(ConvertError::IncorrectPanelRepeat(s.to_owned()))
+        }
+        (min, max) => Ok(Self { min, max }),
+      },
+      _ => Err(ConvertError::IncorrectPanelRepeat(s.to_owned())),
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub(crate) struct RatingSpec {
+  #[serde(rename = "RateMin", skip_serializing_if = "Option::is_none")]
+  pub min: Option<u64>,
+
+  #[serde(rename = "RateMax", skip_serializing_if = "Option::is_none")]
+  pub max: Option<u64>,
+
+  #[serde(rename = "RateStep", skip_serializing_if = "Option::is_none")]
+  pub step: Option<u64>,
+
+  #[serde(rename = "MinRateDescription", skip_serializing_if = "Option::is_none")]
+  pub min_label: Option<String>,
+
+  #[serde(rename = "MaxRateDescription", skip_serializing_if = "Option::is_none")]
+  pub max_label: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone)]
+pub(crate) struct FileSize(pub u64);
+
+impl FromStr for FileSize {
+  type Err = ConvertError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let upper = s.trim().to_uppercase();
+    let (number, multiplier) = match upper.as_str() {
+      n if n.ends_with("GB") => (n.trim_end_matches("GB"), 1024 * 1024 * 1024),
+      n if n.ends_with("MB") => (n.trim_end_matches("MB"), 1024 * 1024),
+      n if n.ends_with("KB") => (n.trim_end_matches("KB"), 1024),
+      n => (n.trim_end_matches('B'), 1),
+    };
+    number
+      .trim()
+      .parse::<u64>()
+      .ok()
+      .and_then(|n| n.checked_mul(multiplier))
+      .map(Self)
+      .ok_or_else(|| ConvertError::IncorrectFileSize(s.to_owned()))
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub(crate) struct FileSpec {
+  #[serde(rename = "AcceptedTypes", skip_serializing_if = "Option::is_none")]
+  pub accepted_types: Option<String>,
+
+  #[serde(rename = "MaxSize", skip_serializing_if = "Option::is_none")]
+  pub max_size: Option<u64>,
+
+  #[serde(rename = "AllowMultiple")]
+  pub allow_multiple: bool,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub(crate) struct ProcessedData {
   #[serde(rename = "Placeholder", skip_serializing_if = "Option::is_none")]
@@ -126,8 +379,35 @@ pub(crate) struct ProcessedData {
   #[serde(rename = "PriceMax", skip_serializing_if = "Option::is_none")]
   pub price_max: Option<u64>,
 
+  #[serde(rename = "MaxLength", skip_serializing_if = "Option::is_none")]
+  pub max_length: Option<u64>,
+
   #[serde(rename = "Visible", skip_serializing_if = "Option::is_none")]
   pub visible: Option<String>,
+
+  #[serde(rename = "DefaultValue", skip_serializing_if = "Option::is_none")]
+  pub default_value: Option<Value>,
+
+  #[serde(rename = "AllRowsRequired", skip_serializing_if = "Option::is_none")]
+  pub all_rows_required: Option<bool>,
+
+  #[serde(flatten, skip_serializing_if = "Option::is_none")]
+  pub rating: Option<RatingSpec>,
+
+  #[serde(rename = "LabelTrue", skip_serializing_if = "Option::is_none")]
+  pub label_true: Option<String>,
+
+  #[serde(rename = "LabelFalse", skip_serializing_if = "Option::is_none")]
+  pub label_false: Option<String>,
+
+  #[serde(flatten, skip_serializing_if = "Option::is_none")]
+  pub file: Option<FileSpec>,
+
+  #[serde(rename = "SelectToRank", skip_serializing_if = "Option::is_none")]
+  pub select_to_rank: Option<bool>,
+
+  #[serde(rename = "Expression", skip_serializing_if = "Option::is_none")]
+  pub expression: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -146,6 +426,18 @@ pub(crate) struct Validator {
 
   #[serde(rename = "MaxLength", skip_serializing_if = "Option::is_none")]
   pub max_length: Option<u64>,
+
+  #[serde(skip)]
+  pub rule: Option<ExpressionRule>,
+}
+
+// What an expression validator checks, so other output schemas don't have to parse the expression
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) enum ExpressionRule {
+  Regex(String),
+  Range(NumInputSpec),
+  Exclusive(Vec<String>),
+  Checked,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -1,4 +1,4 @@
-use super::subtypes::{InputSpec, Validator, ValidatorType};
+use super::subtypes::{ExpressionRule, InputSpec, Validator, ValidatorType};
 use super::Field;
 use crate::converter::error::{ConvertError, Result};
 use crate::converter::field::subtypes::{NumInputSpec, OptionType};
@@ -11,49 +11,38 @@ impl Field {
     input_spec: &Option<InputSpec>,
     num_input_spec: &Option<NumInputSpec>,
     num_input_spec_error: &Option<String>,
-  ) -> Vec<Validator> {
+  ) -> Result<Vec<Validator>> {
     let mut validators = Vec::<Validator>::new();
-    if let (Some(mn), Some(mx)) = (min, max) {
-      if mn == mx {
-        validators.push(Validator {
-          validator_type: ValidatorType::Text,
-          text: format!("{}文字で入力してください", mx),
-          min_length: *min,
-          max_length: *max,
-          expression: None,
-        });
-      }
-      if let (Some(val), Some(error_text)) = (num_input_spec, num_input_spec_error) {
-        validators.push(Validator {
-          validator_type: ValidatorType::Expression,
-          text: error_text.to_owned(),
-          min_length: None,
-          max_length: None,
-          expression: Some(format!(
-            "${{{0}}} && ${{{0}}} >= {1} && ${{{0}}} < {2}",
-            field_name, val.min, val.max
-          )),
-        });
-      }
+    if let Some(vld) = Self::length_validator(min, max)? {
+      validators.push(vld);
+    }
+    if let Some(val) = num_input_spec {
+      validators.push(Validator {
+        validator_type: ValidatorType::Expression,
+        text: num_input_spec_error
+          .to_owned()
+          .unwrap_or_else(|| val.error_text()),
+        min_length: None,
+        max_length: None,
+        expression: Some(val.expression(field_name)),
+        rule: Some(ExpressionRule::Range(*val)),
+      });
     }
 
     if let Some(inp_spec) = input_spec {
       match inp_spec {
         InputSpec::HalfWidthNumber => {
-          if let (Some(mn), Some(mx)) = (*min, *max) {
-            if mx == 2 && mn == 1 {
-              validators.push(Validator {
-                validator_type: ValidatorType::Expression,
-                text: "入力できるのは半角数字のみです".to_owned(),
-                min_length: None,
-                max_length: None,
-                expression: Some(format!(
-                  "${{{0}}} && ${{{0}}}.match(/^[0-9]+$/)",
-                  field_name
-                )),
-              });
-            }
-          }
+          validators.push(Validator {
+            validator_type: ValidatorType::Expression,
+            text: "入力できるのは半角数字のみです".to_owned(),
+            min_length: None,
+            max_length: None,
+            expression: Some(format!(
+              "${{{0}}} && ${{{0}}}.match(/^[0-9]+$/)",
+              field_name
+            )),
+            rule: Some(ExpressionRule::Regex("^[0-9]+$".to_owned())),
+          });
         }
         InputSpec::HalfWidthKanji => {
           validators.push(Validator {
@@ -65,29 +54,111 @@ impl Field {
               "${{{0}}} && ${{{0}}}.match(/^([a-zA-Z])+$/)",
               field_name
             )),
+            rule: Some(ExpressionRule::Regex("^([a-zA-Z])+$".to_owned())),
           });
         }
       }
     }
 
-    validators
+    Ok(validators)
+  }
+
+  pub(super) fn textarea_validators(
+    min: &Option<u64>,
+    max_chars: &Option<u64>,
+  ) -> Result<Vec<Validator>> {
+    let mut validators = Vec::<Validator>::new();
+
+    if let Some(vld) = Self::length_validator(min, max_chars)? {
+      validators.push(vld);
+    }
+    Ok(validators)
+  }
+
+  fn length_validator(min: &Option<u64>, max: &Option<u64>) -> Result<Option<Validator>> {
+    let text = match (min, max) {
+      (Some(mn), Some(mx)) if mn > mx => {
+        return Err(ConvertError::IncorrectLengthRange(*mn, *mx));
+      }
+      (Some(mn), Some(mx)) if mn == mx => format!("{}文字で入力してください", mx),
+      (Some(mn), Some(mx)) => format!("{}文字以上{}文字以内で入力してください", mn, mx),
+      (Some(mn), None) => format!("{}文字以上で入力してください", mn),
+      (None, Some(mx)) => format!("{}文字以内で入力してください", mx),
+      (None, None) => return Ok(None),
+    };
+    Ok(Some(Validator {
+      validator_type: ValidatorType::Text,
+      text,
+      min_length: *min,
+      max_length: *max,
+      expression: None,
+      rule: None,
+    }))
   }
 
-  pub(super) fn textarea_validators(min: &Option<u64>) -> Vec<Validator> {
+  pub(super) fn boolean_validators(field_name: &str, is_required: bool) -> Vec<Validator> {
     let mut validators = Vec::<Validator>::new();
 
-    if let Some(mn) = min {
+    // A required consent must be checked, answering "false" is not enough
+    if is_required {
       validators.push(Validator {
-        validator_type: ValidatorType::Text,
-        text: format!("{}文字以上で入力してください", mn),
-        min_length: *min,
+        validator_type: ValidatorType::Expression,
+        text: "チェックを入れてください".to_owned(),
+        min_length: None,
         max_length: None,
-        expression: None,
+        expression: Some(format!("${{{0}}} === true", field_name)),
+        rule: Some(ExpressionRule::Checked),
       });
     }
     validators
   }
 
+  pub(super) fn file_validators(min: &Option<u64>, max: &Option<u64>) -> Vec<Validator> {
+    let mut validators = Vec::<Validator>::new();
+    let text = match (min, max) {
+      (Some(mn), Some(mx)) => format!(
+        "ファイルは{}個以上{}個以下でアップロードしてください",
+        mn, mx
+      ),
+      (Some(mn), None) => format!("ファイルは{}個以上アップロードしてください", mn),
+      (None, Some(mx)) => format!("ファイルは{}個以下でアップロードしてください", mx),
+      _ => return validators,
+    };
+    validators.push(Validator {
+      validator_type: ValidatorType::AnswerCount,
+      text,
+      min_length: *min,
+      max_length: *max,
+      expression: None,
+      rule: None,
+    });
+    validators
+  }
+
+  pub(super) fn ranking_validators(
+    max: &Option<u64>,
+    options: &Option<Vec<OptionType>>,
+  ) -> Result<Vec<Validator>> {
+    let mut validators = Vec::<Validator>::new();
+
+    if let Some(mx) = max {
+      if let Some(opts) = options {
+        if *mx > opts.len() as u64 {
+          return Err(ConvertError::RankLimitExceedsOptions(*mx));
+        }
+      }
+      validators.push(Validator {
+        validator_type: ValidatorType::AnswerCount,
+        text: format!("上位{}位まで選択してください", mx),
+        min_length: None,
+        max_length: *max,
+        expression: None,
+        rule: None,
+      });
+    }
+    Ok(validators)
+  }
+
   pub(super) fn multiselect_validators(
     field_name: &str,
     min: &Option<u64>,
@@ -103,6 +174,7 @@ impl Field {
         min_length: *min,
         max_length: *max,
         expression: None,
+        rule: None,
       }),
       (Some(mn), None) => validators.push(Validator {
         validator_type: ValidatorType::AnswerCount,
@@ -110,6 +182,7 @@ impl Field {
         min_length: *min,
         max_length: *max,
         expression: None,
+        rule: None,
       }),
       (None, Some(mx)) => validators.push(Validator {
         validator_type: ValidatorType::AnswerCount,
@@ -117,6 +190,7 @@ impl Field {
         min_length: *min,
         max_length: *max,
         expression: None,
+        rule: None,
       }),
       _ => (),
     }
@@ -144,11 +218,13 @@ impl Field {
                     max_length: None,
                     expression: Some(format!("${{{0}}} && (${{{0}}}.includes('{1}') && ${{{0}}}.length === 1) || !${{{0}}}.includes('{1}')",
                                         field_name, exceptions.get(0).unwrap())),
+                    rule: Some(ExpressionRule::Exclusive(exceptions.iter().map(|s| s.to_string()).collect())),
                 }),
                 _ => {
                     let formatted_exceptions_text = exceptions.iter()
                         .map(|&s| format!("[{}]", s))
                         .collect::<Vec<String>>().join("または");
+                    let exclusive = exceptions.iter().map(|s| s.to_string()).collect::<Vec<String>>();
                     let formatted_exceptions_expression = exceptions.into_iter()
                         .map(|s| format!("'{}'",s))
                         .collect::<Vec<String>>().join(", ");
@@ -158,7 +234,8 @@ impl Field {
                         min_length: None,
                         max_length: None,
                         expression: Some(format!("${{{0}}} && (${{{0}}}.some(item => [{1}].includes(item)) && ${{{0}}}.length === 1) || !${{{0}}}.some(item => [{1}].includes(item))",
-                                                 field_name, formatted_exceptions_expression))
+                                                 field_name, formatted_exceptions_expression)),
+                        rule: Some(ExpressionRule::Exclusive(exclusive)),
 
                     })
                 }
@@ -0,0 +1,52 @@
+use super::error::ConvertError;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+pub(crate) const METADATA_SHEET_NAME: &str = "フォーム設定";
+
+#[derive(Serialize, Deserialize, Debug)]
+pub(crate) enum MetadataKey {
+  #[serde(rename = "タイトル")]
+  Title,
+  #[serde(rename = "説明")]
+  Description,
+  #[serde(rename = "完了メッセージ")]
+  CompletionText,
+  #[serde(rename = "フォームID")]
+  FormId,
+  #[serde(rename = "バージョン")]
+  Version,
+}
+
+impl FromStr for MetadataKey {
+  type Err = ConvertError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "タイトル" => Ok(MetadataKey::Title),
+      "説明" => Ok(MetadataKey::Description),
+      "完了メッセージ" => Ok(MetadataKey::CompletionText),
+      "フォームID" => Ok(MetadataKey::FormId),
+      "バージョン" => Ok(MetadataKey::Version),
+      unknown_key => Err(ConvertError::IncorrectMetadataKey(unknown_key.to_owned())),
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub(crate) struct FormMetadata {
+  #[serde(rename = "FormId", skip_serializing_if = "Option::is_none")]
+  pub form_id: Option<String>,
+
+  #[serde(rename = "Version", skip_serializing_if = "Option::is_none")]
+  pub version: Option<String>,
+
+  #[serde(rename = "Title", skip_serializing_if = "Option::is_none")]
+  pub title: Option<String>,
+
+  #[serde(rename = "Description", skip_serializing_if = "Option::is_none")]
+  pub description: Option<String>,
+
+  #[serde(rename = "CompletionText", skip_serializing_if = "Option::is_none")]
+  pub completion_text: Option<String>,
+}
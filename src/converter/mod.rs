@@ -1,9 +1,11 @@
-use crate::converter::error::ConvertError;
+use crate::converter::error::{ConvertError, ConvertWarning};
 use napi::{CallContext, Env, JsNumber, JsObject, JsUnknown, Result, Task};
 use serde::{Deserialize, Serialize};
+use writer::{OutputFormat, OutputOptions, OutputTarget};
 
 mod error;
 mod field;
+mod metadata;
 mod reader;
 mod subject;
 mod test;
@@ -14,6 +16,16 @@ mod writer;
 struct ConvertAsync {
   input_path: String,
   output_path: String,
+  #[serde(default)]
+  target: Option<OutputTarget>,
+  #[serde(default)]
+  format: Option<OutputFormat>,
+  #[serde(default)]
+  indent: Option<u32>,
+  #[serde(default)]
+  no_overwrite: Option<bool>,
+  #[serde(default)]
+  create_parent_dirs: Option<bool>,
 }
 
 impl Task for ConvertAsync {
@@ -21,7 +33,14 @@ impl Task for ConvertAsync {
   type JsValue = JsNumber;
 
   fn compute(&mut self) -> Result<Self::Output> {
-    convert(&self.input_path, &self.output_path)?;
+    let options = OutputOptions {
+      target: self.target.unwrap_or_default(),
+      format: self.format.unwrap_or_default(),
+      indent: self.indent.map(|n| n as usize),
+      no_overwrite: self.no_overwrite.unwrap_or_default(),
+      create_parent_dirs: self.create_parent_dirs.unwrap_or_default(),
+    };
+    convert(&self.input_path, &self.output_path, &options)?;
     Ok(1)
   }
 
@@ -30,6 +49,26 @@ impl Task for ConvertAsync {
   }
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct CheckAsync {
+  input_path: String,
+}
+
+impl Task for CheckAsync {
+  type Output = Vec<String>;
+  type JsValue = JsUnknown;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    let warnings = check(&self.input_path)?;
+    Ok(warnings.iter().map(|w| w.to_string()).collect())
+  }
+
+  fn resolve(self, env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    env.to_js_value(&output)
+  }
+}
+
 #[js_function(1)]
 pub fn convert_async(ctx: CallContext) -> Result<JsObject> {
   let arg0 = ctx.get::<JsUnknown>(0)?;
@@ -38,12 +77,25 @@ pub fn convert_async(ctx: CallContext) -> Result<JsObject> {
   Ok(async_task.promise_object())
 }
 
-fn convert(input_path: &str, ouput_path: &str) -> error::Result<()> {
+#[js_function(1)]
+pub fn check_async(ctx: CallContext) -> Result<JsObject> {
+  let arg0 = ctx.get::<JsUnknown>(0)?;
+  let task: CheckAsync = ctx.env.from_js_value(arg0)?;
+  let async_task = ctx.env.spawn(task)?;
+  Ok(async_task.promise_object())
+}
+
+fn convert(input_path: &str, ouput_path: &str, options: &OutputOptions) -> error::Result<()> {
   let page = reader::parse(input_path)?;
-  writer::write_to_file(page, ouput_path)?;
+  writer::write_to_file(&page, ouput_path, options)?;
   Ok(())
 }
 
+// Problems that don't stop the conversion, read without writing any output
+fn check(input_path: &str) -> error::Result<Vec<ConvertWarning>> {
+  Ok(reader::parse(input_path)?.warnings())
+}
+
 impl From<ConvertError> for napi::Error {
   fn from(err: ConvertError) -> Self {
     // napi::Error::from_reason(format!("{:?}", err))
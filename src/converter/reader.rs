@@ -1,57 +1,109 @@
 use super::error::{ConvertError, Result};
 use super::field::{
+  element::{Content, ContentVariant, Element, Panel},
+  source::SourcePosition,
   subtypes::{FieldVariant, InputSpec},
   Field, Page,
 };
+use super::metadata::{FormMetadata, MetadataKey, METADATA_SHEET_NAME};
 use super::subject::Subject;
-use crate::converter::field::subtypes::{NumInputSpec, OptionType};
-use calamine::{open_workbook, DataType, Reader, Xlsx};
+use crate::converter::field::subtypes::{NumInputSpec, OptionType, RepeatSpec};
+use calamine::{open_workbook, DataType, Ods, Range, Reader, Sheets, Xls, Xlsb, Xlsx};
+use std::fs::File;
+use std::io::{BufReader, Read};
+
+const ZIP_SIGNATURE: [u8; 4] = [0x50, 0x4b, 0x03, 0x04];
+const CFB_SIGNATURE: [u8; 8] = [0xd0, 0xcf, 0x11, 0xe0, 0xa1, 0xb1, 0x1a, 0xe1];
 
 pub(crate) fn parse(path: &str) -> Result<Page> {
-  let mut workbook: Xlsx<_> = open_workbook(path)?;
+  let mut workbook = open_workbook_by_content(path)?;
+  // The form is read from the first sheet that is not the settings sheet
+  let sheet_name = workbook
+    .sheet_names()
+    .iter()
+    .find(|&name| name != METADATA_SHEET_NAME)
+    .cloned()
+    .ok_or(ConvertError::NoWorksheet)?;
   let worksheet = workbook
-    .worksheet_range_at(0)
+    .worksheet_range(&sheet_name)
     .ok_or(ConvertError::NoWorksheet)??;
+  let (start_row, start_col) = worksheet.start().unwrap_or((0, 0));
+  let metadata = match workbook.worksheet_range(METADATA_SHEET_NAME) {
+    Some(range) => parse_metadata(&range?)?,
+    None => FormMetadata::default(),
+  };
 
-  let mut fields = Vec::<Field>::new();
+  let mut elements = Vec::<Element>::new();
+  let mut panel: Option<Panel> = None;
+  let mut panel_cnt = 0usize;
   let max_field_cnt = 100usize;
 
   'field_loop: for field_index in 1..max_field_cnt {
     let field_name = format!("field{}", field_index);
     let mut is_required: bool = false;
+    let mut panel_title: Option<String> = None;
+    let mut panel_required: Option<bool> = None;
+    let mut panel_visible: Option<String> = None;
+    let mut panel_repeat: Option<RepeatSpec> = None;
     let mut variant: FieldVariant = FieldVariant::Text;
+    let mut content_variant: Option<ContentVariant> = None;
+    let mut visible_condition: Option<String> = None;
     let mut min: Option<u64> = None;
     let mut max: Option<u64> = None;
+    let mut max_chars: Option<u64> = None;
+    let mut step: Option<u64> = None;
+    let mut min_label: Option<String> = None;
+    let mut max_label: Option<String> = None;
+    let mut true_label: Option<String> = None;
+    let mut false_label: Option<String> = None;
+    let mut calculation: Option<String> = None;
+    let mut file_types: Option<Vec<String>> = None;
+    let mut max_file_size: Option<u64> = None;
     let mut label: String = "".to_owned();
+    let mut description: Option<String> = None;
     let mut placeholder_text: Option<String> = None;
     let mut input_spec: Option<InputSpec> = None;
     let mut num_input_spec: Option<NumInputSpec> = None;
     let mut num_input_spec_error: Option<String> = None;
     let mut options_from_key: Option<String> = None;
+    let mut default_value: Option<String> = None;
+    let mut all_rows_required: Option<bool> = None;
     let mut options = Vec::<OptionType>::new();
+    let mut matrix_rows = Vec::<OptionType>::new();
     // TODO: implement deserialize and serialize logic for display conditions
     let display_condition_first = Vec::<String>::new();
     let display_condition_second = Vec::<String>::new();
     let display_condition_third = Vec::<String>::new();
 
     let mut ignore_options = false;
+    let mut ignore_matrix_rows = false;
+
+    let col_index = if field_index == 1 {
+      1
+    } else {
+      (field_index - 1) * 2 + 1
+    };
+    let mut source = SourcePosition::new(&sheet_name, start_col + col_index as u32);
 
     let rows = worksheet.rows();
-    // 'row_loop: while let Some(row) = rows.next() {
-    'row_loop: for row in rows {
+    for (row_index, row) in rows.enumerate() {
       let subject: Subject;
+      let subject_name: &str;
       if let DataType::String(s) = row.get(0).unwrap() {
         subject = s.parse::<Subject>()?;
+        subject_name = s;
       } else {
         continue;
       }
-      let col_index = if field_index == 1 {
-        1
-      } else {
-        (field_index - 1) * 2 + 1
-      };
       let dt = row.get(col_index).unwrap();
       let dt_next = row.get(col_index + 1).unwrap();
+      let row_number = start_row + row_index as u32;
+      for (offset, cell) in [dt, dt_next].iter().enumerate() {
+        if !cell.is_empty() {
+          let column = start_col + (col_index + offset) as u32;
+          source.push(subject_name, row_number, column, cell.to_string());
+        }
+      }
       match subject {
         Subject::Required => {
           let has_required = Field::required_from_datatype(dt)?;
@@ -65,7 +117,29 @@ pub(crate) fn parse(path: &str) -> Result<Page> {
           }
           // if let IsRequired::NA = is_required { field_cnt = field_index + 1; }
         }
+        Subject::Panel => {
+          panel_title = Field::optional_string_from_datatype(dt)?;
+        }
+        Subject::PanelRequired => {
+          panel_required = Field::required_from_datatype(dt)?;
+        }
+        Subject::PanelVisible => {
+          panel_visible = Field::optional_string_from_datatype(dt)?;
+        }
+        Subject::PanelRepeat => {
+          panel_repeat = Field::repeat_specification_from_datatype(dt)?;
+        }
+        Subject::VisibleCondition => {
+          visible_condition = Field::optional_string_from_datatype(dt)?;
+        }
         Subject::Type => {
+          // Headings and text blocks are not questions, they skip the field variant
+          if let DataType::String(s) = dt {
+            if let Ok(cv) = s.parse::<ContentVariant>() {
+              content_variant = Some(cv);
+              continue;
+            }
+          }
           variant = Field::variant_from_datatype(dt)?;
           ignore_options = match variant {
             FieldVariant::TextArea | FieldVariant::Text => true,
@@ -78,9 +152,39 @@ pub(crate) fn parse(path: &str) -> Result<Page> {
         Subject::Min => {
           min = Field::optional_u64_from_datatype(dt)?;
         }
+        Subject::MaxChars => {
+          max_chars = Field::optional_u64_from_datatype(dt)?;
+        }
+        Subject::Step => {
+          step = Field::optional_u64_from_datatype(dt)?;
+        }
+        Subject::MaxLabel => {
+          max_label = Field::optional_string_from_datatype(dt)?;
+        }
+        Subject::MinLabel => {
+          min_label = Field::optional_string_from_datatype(dt)?;
+        }
+        Subject::TrueLabel => {
+          true_label = Field::optional_string_from_datatype(dt)?;
+        }
+        Subject::FalseLabel => {
+          false_label = Field::optional_string_from_datatype(dt)?;
+        }
+        Subject::Calculation => {
+          calculation = Field::optional_string_from_datatype(dt)?;
+        }
+        Subject::FileTypes => {
+          file_types = Field::file_types_from_datatype(dt)?;
+        }
+        Subject::MaxFileSize => {
+          max_file_size = Field::file_size_from_datatype(dt)?;
+        }
         Subject::Label => {
           label = Field::label_from_datatype(dt)?;
         }
+        Subject::Description => {
+          description = Field::multiline_string_from_datatype(dt)?;
+        }
         Subject::Placeholder => {
           let field_ref = Field::field_number_from_datatype(dt_next);
           match field_ref {
@@ -103,17 +207,41 @@ pub(crate) fn parse(path: &str) -> Result<Page> {
         Subject::NumInputSpecError => {
           num_input_spec_error = Field::optional_string_from_datatype(dt)?;
         }
+        Subject::Default => {
+          default_value = Field::optional_string_from_datatype(dt)?;
+        }
         Subject::Options => {
           if ignore_options {
-            break;
+            continue;
           }
           let option = Field::optional_string_from_datatype(dt)?;
           match option {
             Some(s) => {
               options.push(OptionType::new(s.to_owned()));
             }
+            // Options end at the first empty row, following rows may hold other subjects
             None => {
-              break 'row_loop;
+              ignore_options = true;
+            }
+          }
+        }
+        Subject::MatrixAllRowsRequired => {
+          all_rows_required = Field::required_from_datatype(dt)?;
+        }
+        Subject::MatrixRows => {
+          if ignore_matrix_rows || !matches!(variant, FieldVariant::Matrix) {
+            continue;
+          }
+          let matrix_row = Field::optional_string_from_datatype(dt)?;
+          match matrix_row {
+            Some(s) => {
+              matrix_rows.push(OptionType::with_label(
+                format!("{}_{}", field_name, matrix_rows.len() + 1),
+                s,
+              ));
+            }
+            None => {
+              ignore_matrix_rows = true;
             }
           }
         }
@@ -126,25 +254,138 @@ pub(crate) fn parse(path: &str) -> Result<Page> {
     let display_condition_second = Field::vec_to_optional_vec(display_condition_second);
     let display_condition_third = Field::vec_to_optional_vec(display_condition_third);
     let options = Field::vec_to_optional_vec(options);
+    let matrix_rows = Field::vec_to_optional_vec(matrix_rows);
 
-    fields.push(Field::new(
+    // Consecutive fields with the same group title are put in one panel
+    match panel_title {
+      Some(title) => {
+        if !matches!(panel, Some(ref p) if p.title() == title) {
+          if let Some(p) = panel.take() {
+            elements.push(p.into_element());
+          }
+          panel_cnt += 1;
+          panel = Some(Panel::new(format!("panel{}", panel_cnt), title));
+        }
+      }
+      None => {
+        if let Some(p) = panel.take() {
+          elements.push(p.into_element());
+        }
+      }
+    }
+    if let Some(ref mut p) = panel {
+      p.merge_settings(panel_required, panel_visible, panel_repeat);
+    }
+
+    if let Some(cv) = content_variant {
+      let mut content = Content::new(field_name, cv, label, visible_condition);
+      content.set_source(source);
+      match panel {
+        Some(ref mut p) => p.push(Element::Content(content)),
+        None => elements.push(Element::Content(content)),
+      }
+      continue;
+    }
+
+    // The template pre-fills 最小 of text fields with 1, which Required already covers, so it is
+    // only kept as an exact length of 1
+    if let (FieldVariant::Text, Some(1)) = (&variant, min) {
+      if max_chars.or(max) != Some(1) {
+        min = None;
+      }
+    }
+
+    let mut field = Field::new(
       is_required,
       field_name,
       variant,
       label,
+      description,
       placeholder_text,
       input_spec,
       num_input_spec,
       num_input_spec_error,
       options,
       options_from_key,
+      matrix_rows,
+      all_rows_required,
+      default_value,
       max,
       min,
+      max_chars,
+      step,
+      max_label,
+      min_label,
+      true_label,
+      false_label,
+      calculation,
+      visible_condition,
+      file_types,
+      max_file_size,
       display_condition_first,
       display_condition_second,
       display_condition_third,
-    )?);
+    )?;
+    field.set_source(source);
+    match panel {
+      Some(ref mut p) => p.push(Element::Field(field)),
+      None => elements.push(Element::Field(field)),
+    }
+  }
+  if let Some(p) = panel {
+    elements.push(p.into_element());
+  }
+
+  let page = Page::new(metadata, elements);
+  page.check_calculations()?;
+  Ok(page)
+}
+
+// The file type is told from the file itself, templates are often sent with the wrong extension
+fn open_workbook_by_content(path: &str) -> Result<Sheets<BufReader<File>>> {
+  let mut header = Vec::<u8>::with_capacity(CFB_SIGNATURE.len());
+  File::open(path)?
+    .take(CFB_SIGNATURE.len() as u64)
+    .read_to_end(&mut header)?;
+
+  if header.starts_with(&ZIP_SIGNATURE) {
+    // xlsx, xlsb and ods are all zip packages, only their contents differ
+    let xlsx_error = match open_workbook::<Xlsx<_>, _>(path) {
+      Ok(workbook) => return Ok(Sheets::Xlsx(workbook)),
+      Err(err) => err,
+    };
+    if let Ok(workbook) = open_workbook::<Xlsb<_>, _>(path) {
+      return Ok(Sheets::Xlsb(workbook));
+    }
+    if let Ok(workbook) = open_workbook::<Ods<_>, _>(path) {
+      return Ok(Sheets::Ods(workbook));
+    }
+    // A zip that is none of them is most likely a damaged xlsx
+    return Err(xlsx_error.into());
+  } else if header.starts_with(&CFB_SIGNATURE) {
+    let workbook = open_workbook::<Xls<_>, _>(path).map_err(calamine::Error::Xls)?;
+    return Ok(Sheets::Xls(workbook));
   }
+  Err(ConvertError::UnsupportedWorkbook(path.to_owned()))
+}
 
-  Ok(Page::new(fields))
+fn parse_metadata(range: &Range<DataType>) -> Result<FormMetadata> {
+  let mut metadata = FormMetadata::default();
+  for row in range.rows() {
+    let key = match row.first() {
+      Some(DataType::String(s)) if !s.is_empty() => s.parse::<MetadataKey>()?,
+      _ => continue,
+    };
+    let dt = row.get(1).unwrap_or(&DataType::Empty);
+    match key {
+      MetadataKey::Title => metadata.title = Field::optional_string_from_datatype(dt)?,
+      MetadataKey::Description => metadata.description = Field::multiline_string_from_datatype(dt)?,
+      MetadataKey::CompletionText => {
+        metadata.completion_text = Field::multiline_string_from_datatype(dt)?
+      }
+      MetadataKey::FormId => metadata.form_id = Field::optional_string_from_datatype(dt)?,
+      MetadataKey::Version => metadata.version = Field::optional_string_from_datatype(dt)?,
+    }
+  }
+  Ok(metadata)
 }
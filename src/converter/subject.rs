@@ -8,20 +8,50 @@ pub(crate) enum Subject {
   Paging,
   #[serde(rename = "表示")]
   Required,
+  #[serde(rename = "グループ")]
+  Panel,
+  #[serde(rename = "グループ必須")]
+  PanelRequired,
+  #[serde(rename = "グループ表示条件")]
+  PanelVisible,
+  #[serde(rename = "グループ繰り返し")]
+  PanelRepeat,
   #[serde(rename = "表示条件1")]
   DisplayConditionFirst,
   #[serde(rename = "表示条件2")]
   DisplayConditionSecond,
   #[serde(rename = "表示条件3")]
   DisplayConditionThird,
+  #[serde(rename = "表示条件式")]
+  VisibleCondition,
   #[serde(rename = "タイプ")]
   Type,
   #[serde(rename = "最大")]
   Max,
   #[serde(rename = "最小")]
   Min,
+  #[serde(rename = "最大文字数")]
+  MaxChars,
+  #[serde(rename = "刻み")]
+  Step,
+  #[serde(rename = "最大ラベル")]
+  MaxLabel,
+  #[serde(rename = "最小ラベル")]
+  MinLabel,
+  #[serde(rename = "はいラベル")]
+  TrueLabel,
+  #[serde(rename = "いいえラベル")]
+  FalseLabel,
+  #[serde(rename = "計算式")]
+  Calculation,
+  #[serde(rename = "ファイル形式")]
+  FileTypes,
+  #[serde(rename = "最大ファイルサイズ")]
+  MaxFileSize,
   #[serde(rename = "ラベル")]
   Label,
+  #[serde(rename = "説明")]
+  Description,
   #[serde(rename = "プレースホルダ")]
   Placeholder,
   #[serde(rename = "入力指定")]
@@ -30,8 +60,14 @@ pub(crate) enum Subject {
   NumInputSpec,
   #[serde(rename = "入力指定エラー文言")]
   NumInputSpecError,
+  #[serde(rename = "デフォルト")]
+  Default,
   #[serde(rename = "プルダウン")]
   Options,
+  #[serde(rename = "マトリクス行")]
+  MatrixRows,
+  #[serde(rename = "マトリクス全行必須")]
+  MatrixAllRowsRequired,
 }
 
 impl FromStr for Subject {
@@ -41,18 +77,36 @@ impl FromStr for Subject {
     match s {
       "ページング" => Ok(Subject::Paging),
       "表示" => Ok(Subject::Required),
+      "グループ" => Ok(Subject::Panel),
+      "グループ必須" => Ok(Subject::PanelRequired),
+      "グループ表示条件" => Ok(Subject::PanelVisible),
+      "グループ繰り返し" => Ok(Subject::PanelRepeat),
       "表示条件1" => Ok(Subject::DisplayConditionFirst),
       "表示条件2" => Ok(Subject::DisplayConditionSecond),
       "表示条件3" => Ok(Subject::DisplayConditionThird),
+      "表示条件式" => Ok(Subject::VisibleCondition),
       "タイプ" => Ok(Subject::Type),
       "最大" => Ok(Subject::Max),
       "最小" => Ok(Subject::Min),
+      "最大文字数" => Ok(Subject::MaxChars),
+      "刻み" => Ok(Subject::Step),
+      "最大ラベル" => Ok(Subject::MaxLabel),
+      "最小ラベル" => Ok(Subject::MinLabel),
+      "はいラベル" => Ok(Subject::TrueLabel),
+      "いいえラベル" => Ok(Subject::FalseLabel),
+      "計算式" => Ok(Subject::Calculation),
+      "ファイル形式" => Ok(Subject::FileTypes),
+      "最大ファイルサイズ" => Ok(Subject::MaxFileSize),
       "ラベル" => Ok(Subject::Label),
+      "説明" => Ok(Subject::Description),
       "プレースホルダ" => Ok(Subject::Placeholder),
       "入力指定" => Ok(Subject::InputSpec),
       "数字入力指定範囲(例：10~90)" => Ok(Subject::NumInputSpec),
       "入力指定エラー文言" => Ok(Subject::NumInputSpecError),
+      "デフォルト" => Ok(Subject::Default),
+      "マトリクス全行必須" => Ok(Subject::MatrixAllRowsRequired),
       options if options.starts_with("プルダウン") => Ok(Subject::Options),
+      matrix_rows if matrix_rows.starts_with("マトリクス行") => Ok(Subject::MatrixRows),
       unknown_subject => Err(ConvertError::IncorrectSubject(unknown_subject.to_owned())),
     }
   }
@@ -1,7 +1,10 @@
 #[cfg(test)]
 mod convert_tests {
+  use crate::converter::check;
+  use crate::converter::error::{ConvertError, ConvertWarning};
+  use crate::converter::field::subtypes::{FileSize, NumInputSpec, RepeatSpec};
   use crate::converter::reader::parse;
-  use crate::converter::writer;
+  use crate::converter::writer::{self, OutputFormat, OutputOptions, OutputTarget};
 
   static PATH_INPUT_DROPDOWN_TEST: &'static str = "resources/test_dropdown.xlsx";
   static PATH_OUTPUT_DROPDOWN_TEST: &'static str = "resources/test_dropdown_output.json";
@@ -18,11 +21,186 @@ mod convert_tests {
   static PATH_INPUT_RADIO_TEST: &'static str = "resources/test_radio.xlsx";
   static PATH_OUTPUT_RADIO_TEST: &'static str = "resources/test_radio_output.json";
 
+  static PATH_INPUT_DEFAULT_TEST: &'static str = "resources/test_default.xlsx";
+  static PATH_OUTPUT_DEFAULT_TEST: &'static str = "resources/test_default_output.json";
+  static PATH_INPUT_DEFAULT_OUT_OF_RANGE_TEST: &'static str =
+    "resources/test_default_out_of_range.xlsx";
+  static PATH_INPUT_DEFAULT_NOT_NUMBER_TEST: &'static str =
+    "resources/test_default_not_number.xlsx";
+  static PATH_INPUT_DEFAULT_TOO_LONG_TEST: &'static str = "resources/test_default_too_long.xlsx";
+
+  static PATH_INPUT_DESCRIPTION_TEST: &'static str = "resources/test_description.xlsx";
+  static PATH_OUTPUT_DESCRIPTION_TEST: &'static str = "resources/test_description_output.json";
+
+  static PATH_INPUT_METADATA_TEST: &'static str = "resources/test_metadata.xlsx";
+  static PATH_OUTPUT_METADATA_TEST: &'static str = "resources/test_metadata_output.json";
+
+  static PATH_INPUT_PANEL_TEST: &'static str = "resources/test_panel.xlsx";
+  static PATH_OUTPUT_PANEL_TEST: &'static str = "resources/test_panel_output.json";
+
+  static PATH_INPUT_PANEL_DYNAMIC_TEST: &'static str = "resources/test_panel_dynamic.xlsx";
+  static PATH_OUTPUT_PANEL_DYNAMIC_TEST: &'static str = "resources/test_panel_dynamic_output.json";
+
+  static PATH_INPUT_MATRIX_TEST: &'static str = "resources/test_matrix.xlsx";
+  static PATH_OUTPUT_MATRIX_TEST: &'static str = "resources/test_matrix_output.json";
+
+  static PATH_INPUT_RATING_TEST: &'static str = "resources/test_rating.xlsx";
+  static PATH_OUTPUT_RATING_TEST: &'static str = "resources/test_rating_output.json";
+
+  static PATH_INPUT_BOOLEAN_TEST: &'static str = "resources/test_boolean.xlsx";
+  static PATH_OUTPUT_BOOLEAN_TEST: &'static str = "resources/test_boolean_output.json";
+
+  static PATH_INPUT_FILE_TEST: &'static str = "resources/test_file.xlsx";
+  static PATH_OUTPUT_FILE_TEST: &'static str = "resources/test_file_output.json";
+
+  static PATH_INPUT_CONTENT_TEST: &'static str = "resources/test_content.xlsx";
+  static PATH_OUTPUT_CONTENT_TEST: &'static str = "resources/test_content_output.json";
+
+  static PATH_INPUT_RANKING_TEST: &'static str = "resources/test_ranking.xlsx";
+  static PATH_OUTPUT_RANKING_TEST: &'static str = "resources/test_ranking_output.json";
+
+  static PATH_INPUT_CALCULATED_TEST: &'static str = "resources/test_calculated.xlsx";
+  static PATH_OUTPUT_CALCULATED_TEST: &'static str = "resources/test_calculated_output.json";
+
+  static PATH_INPUT_CALCULATED_CIRCULAR_TEST: &'static str =
+    "resources/test_calculated_circular.xlsx";
+  static PATH_INPUT_CALCULATED_UNKNOWN_TEST: &'static str =
+    "resources/test_calculated_unknown.xlsx";
+
+  static PATH_INPUT_TEXT_LENGTH_TEST: &'static str = "resources/test_text_length.xlsx";
+  static PATH_OUTPUT_TEXT_LENGTH_TEST: &'static str = "resources/test_text_length_output.json";
+
+  static PATH_INPUT_INPUT_SPEC_TEST: &'static str = "resources/test_input_spec.xlsx";
+  static PATH_OUTPUT_INPUT_SPEC_TEST: &'static str = "resources/test_input_spec_output.json";
+
+  static PATH_OUTPUT_TEXT_SURVEYJS_TEST: &'static str = "resources/test_text_surveyjs_output.json";
+
+  static PATH_OUTPUT_MULTISELECT_SURVEYJS_TEST: &'static str =
+    "resources/test_multiselect_surveyjs_output.json";
+
+  static PATH_OUTPUT_PANEL_DYNAMIC_SURVEYJS_TEST: &'static str =
+    "resources/test_panel_dynamic_surveyjs_output.json";
+
+  static PATH_OUTPUT_TEXT_JSON_SCHEMA_TEST: &'static str =
+    "resources/test_text_json_schema_output.json";
+
+  static PATH_OUTPUT_MULTISELECT_JSON_SCHEMA_TEST: &'static str =
+    "resources/test_multiselect_json_schema_output.json";
+
+  static PATH_OUTPUT_MATRIX_JSON_SCHEMA_TEST: &'static str =
+    "resources/test_matrix_json_schema_output.json";
+
+  static PATH_OUTPUT_PANEL_DYNAMIC_JSON_SCHEMA_TEST: &'static str =
+    "resources/test_panel_dynamic_json_schema_output.json";
+
+  static PATH_OUTPUT_MULTISELECT_TYPESCRIPT_TEST: &'static str =
+    "resources/test_multiselect_typescript_output.d.ts";
+
+  static PATH_OUTPUT_MATRIX_TYPESCRIPT_TEST: &'static str =
+    "resources/test_matrix_typescript_output.d.ts";
+
+  static PATH_OUTPUT_PANEL_DYNAMIC_TYPESCRIPT_TEST: &'static str =
+    "resources/test_panel_dynamic_typescript_output.d.ts";
+
+  static PATH_OUTPUT_DESCRIPTION_TYPESCRIPT_TEST: &'static str =
+    "resources/test_description_typescript_output.d.ts";
+
+  static PATH_OUTPUT_TEXT_HTML_TEST: &'static str = "resources/test_text_html_output.html";
+
+  static PATH_OUTPUT_PANEL_HTML_TEST: &'static str = "resources/test_panel_html_output.html";
+
+  static PATH_OUTPUT_CONTENT_HTML_TEST: &'static str = "resources/test_content_html_output.html";
+
+  static PATH_OUTPUT_TEXT_MARKDOWN_TEST: &'static str = "resources/test_text_markdown_output.md";
+
+  static PATH_OUTPUT_PANEL_DYNAMIC_MARKDOWN_TEST: &'static str =
+    "resources/test_panel_dynamic_markdown_output.md";
+
+  static PATH_OUTPUT_MULTISELECT_CSV_TEST: &'static str =
+    "resources/test_multiselect_csv_output.csv";
+
+  static PATH_OUTPUT_MATRIX_CSV_TEST: &'static str = "resources/test_matrix_csv_output.csv";
+
+  static PATH_OUTPUT_TEXT_IR_TEST: &'static str = "resources/test_text_ir_output.json";
+
+  static PATH_OUTPUT_INPUT_SPEC_IR_TEST: &'static str = "resources/test_input_spec_ir_output.json";
+
+  static PATH_OUTPUT_CONTENT_IR_TEST: &'static str = "resources/test_content_ir_output.json";
+
+  static PATH_OUTPUT_TEXT_YAML_TEST: &'static str = "resources/test_text_yaml_output.yaml";
+
+  static PATH_OUTPUT_TEXT_COMPACT_JSON_TEST: &'static str =
+    "resources/test_text_compact_json_output.json";
+
+  static PATH_OUTPUT_TEXT_INDENT_TEST: &'static str = "resources/test_text_indent_output.json";
+
+  static PATH_OUTPUT_TEXT_NO_OVERWRITE_TEST: &'static str =
+    "resources/test_text_no_overwrite_output.json";
+
+  static PATH_OUTPUT_TEXT_UNSUPPORTED_FORMAT_TEST: &'static str =
+    "resources/test_text_unsupported_format_output.d.ts";
+
+  static PATH_INPUT_TEXT_ODS_TEST: &'static str = "resources/test_text.ods";
+
+  // An ods workbook saved with an xlsx extension
+  static PATH_INPUT_PANEL_ODS_TEST: &'static str = "resources/test_panel_ods.xlsx";
+
+  static PATH_INPUT_TEXT_XLS_TEST: &'static str = "resources/test_text.xls";
+
+  static PATH_INPUT_TEXT_XLSB_TEST: &'static str = "resources/test_text.xlsb";
+
+  static PATH_INPUT_CORRUPT_XLSX_TEST: &'static str = "resources/test_corrupt.xlsx";
+
+  static PATH_INPUT_UNSUPPORTED_FORMAT_TEST: &'static str =
+    "resources/test_unsupported_format.xlsx";
+
+  static PATH_INPUT_PANEL_REQUIRED_LATER_TEST: &'static str =
+    "resources/test_panel_required_later.xlsx";
+  static PATH_OUTPUT_PANEL_REQUIRED_LATER_TEST: &'static str =
+    "resources/test_panel_required_later_output.json";
+
+  static PATH_OUTPUT_CALCULATED_SURVEYJS_TEST: &'static str =
+    "resources/test_calculated_surveyjs_output.json";
+
+  static PATH_INPUT_VISIBLE_CONDITION_TEST: &'static str = "resources/test_visible_condition.xlsx";
+  static PATH_OUTPUT_VISIBLE_CONDITION_TEST: &'static str =
+    "resources/test_visible_condition_output.json";
+
+  static PATH_OUTPUT_VISIBLE_CONDITION_SURVEYJS_TEST: &'static str =
+    "resources/test_visible_condition_surveyjs_output.json";
+
+  static PATH_INPUT_VISIBLE_CONDITION_UNSUPPORTED_TEST: &'static str =
+    "resources/test_visible_condition_unsupported.xlsx";
+  static PATH_OUTPUT_VISIBLE_CONDITION_UNSUPPORTED_TEST: &'static str =
+    "resources/test_visible_condition_unsupported_output.json";
+
+  static PATH_OUTPUT_RATING_HTML_TEST: &'static str = "resources/test_rating_html_output.html";
+
   fn test_parse_write(input_path: &'static str, output_path: &'static str) {
+    test_parse_write_target(input_path, output_path, OutputTarget::Internal)
+  }
+
+  fn test_parse_write_target(
+    input_path: &'static str,
+    output_path: &'static str,
+    target: OutputTarget,
+  ) {
+    let options = OutputOptions {
+      target,
+      ..Default::default()
+    };
+    test_parse_write_options(input_path, output_path, &options)
+  }
+
+  fn test_parse_write_options(
+    input_path: &'static str,
+    output_path: &'static str,
+    options: &OutputOptions,
+  ) {
     // let path = Path::new(constants::PATH_INPUT_DROPDOWN_TEST).to_str().unwrap();
     let parse_res = parse(input_path);
     if let Ok(inp) = parse_res {
-      let write_res = writer::write_to_file(inp, output_path);
+      let write_res = writer::write_to_file(&inp, output_path, options);
       if let Ok(_) = write_res {
         assert!(true);
       } else {
@@ -58,4 +236,663 @@ mod convert_tests {
   fn test_radio() {
     test_parse_write(PATH_INPUT_RADIO_TEST, PATH_OUTPUT_RADIO_TEST)
   }
+
+  #[test]
+  fn test_default() {
+    test_parse_write(PATH_INPUT_DEFAULT_TEST, PATH_OUTPUT_DEFAULT_TEST)
+  }
+
+  #[test]
+  fn test_default_out_of_range() {
+    let parse_res = parse(PATH_INPUT_DEFAULT_OUT_OF_RANGE_TEST);
+    assert!(matches!(
+      parse_res,
+      Err(ConvertError::DefaultViolatesInputSpec(_))
+    ));
+  }
+
+  #[test]
+  fn test_default_not_number() {
+    let parse_res = parse(PATH_INPUT_DEFAULT_NOT_NUMBER_TEST);
+    assert!(matches!(
+      parse_res,
+      Err(ConvertError::DefaultViolatesInputSpec(_))
+    ));
+  }
+
+  #[test]
+  fn test_default_too_long() {
+    let parse_res = parse(PATH_INPUT_DEFAULT_TOO_LONG_TEST);
+    assert!(matches!(
+      parse_res,
+      Err(ConvertError::DefaultLengthOutOfRange(_))
+    ));
+  }
+
+  #[test]
+  fn test_description() {
+    test_parse_write(PATH_INPUT_DESCRIPTION_TEST, PATH_OUTPUT_DESCRIPTION_TEST)
+  }
+
+  #[test]
+  fn test_metadata() {
+    test_parse_write(PATH_INPUT_METADATA_TEST, PATH_OUTPUT_METADATA_TEST)
+  }
+
+  #[test]
+  fn test_panel() {
+    test_parse_write(PATH_INPUT_PANEL_TEST, PATH_OUTPUT_PANEL_TEST)
+  }
+
+  #[test]
+  fn test_panel_dynamic() {
+    test_parse_write(
+      PATH_INPUT_PANEL_DYNAMIC_TEST,
+      PATH_OUTPUT_PANEL_DYNAMIC_TEST,
+    )
+  }
+
+  #[test]
+  fn test_matrix() {
+    test_parse_write(PATH_INPUT_MATRIX_TEST, PATH_OUTPUT_MATRIX_TEST)
+  }
+
+  #[test]
+  fn test_rating() {
+    test_parse_write(PATH_INPUT_RATING_TEST, PATH_OUTPUT_RATING_TEST)
+  }
+
+  #[test]
+  fn test_boolean() {
+    test_parse_write(PATH_INPUT_BOOLEAN_TEST, PATH_OUTPUT_BOOLEAN_TEST)
+  }
+
+  #[test]
+  fn test_file() {
+    test_parse_write(PATH_INPUT_FILE_TEST, PATH_OUTPUT_FILE_TEST)
+  }
+
+  #[test]
+  fn test_content() {
+    test_parse_write(PATH_INPUT_CONTENT_TEST, PATH_OUTPUT_CONTENT_TEST)
+  }
+
+  #[test]
+  fn test_ranking() {
+    test_parse_write(PATH_INPUT_RANKING_TEST, PATH_OUTPUT_RANKING_TEST)
+  }
+
+  #[test]
+  fn test_calculated() {
+    test_parse_write(PATH_INPUT_CALCULATED_TEST, PATH_OUTPUT_CALCULATED_TEST)
+  }
+
+  #[test]
+  fn test_calculated_circular() {
+    let parse_res = parse(PATH_INPUT_CALCULATED_CIRCULAR_TEST);
+    assert!(matches!(
+      parse_res,
+      Err(ConvertError::CircularCalculation(_))
+    ));
+  }
+
+  #[test]
+  fn test_calculated_unknown() {
+    let parse_res = parse(PATH_INPUT_CALCULATED_UNKNOWN_TEST);
+    assert!(matches!(
+      parse_res,
+      Err(ConvertError::UnknownFieldReference(_, _))
+    ));
+  }
+
+  #[test]
+  fn test_text_length() {
+    test_parse_write(PATH_INPUT_TEXT_LENGTH_TEST, PATH_OUTPUT_TEXT_LENGTH_TEST)
+  }
+
+  #[test]
+  fn test_num_input_spec_inclusive() {
+    let spec = "10~90".parse::<NumInputSpec>().unwrap();
+    assert_eq!(
+      spec.expression("field1"),
+      "${field1} && ${field1} >= 10 && ${field1} <= 90"
+    );
+    assert_eq!(spec.error_text(), "10以上90以下の数字で入力してください");
+  }
+
+  #[test]
+  fn test_num_input_spec_exclusive() {
+    let spec = "(0~1)".parse::<NumInputSpec>().unwrap();
+    assert_eq!(
+      spec.expression("field1"),
+      "${field1} && ${field1} > 0 && ${field1} < 1"
+    );
+    let spec = "[10~90)".parse::<NumInputSpec>().unwrap();
+    assert_eq!(
+      spec.expression("field1"),
+      "${field1} && ${field1} >= 10 && ${field1} < 90"
+    );
+    assert_eq!(spec.error_text(), "10以上90未満の数字で入力してください");
+  }
+
+  #[test]
+  fn test_num_input_spec_open_ended() {
+    let spec = "~90".parse::<NumInputSpec>().unwrap();
+    assert_eq!(spec.expression("field1"), "${field1} && ${field1} <= 90");
+    let spec = "(10~".parse::<NumInputSpec>().unwrap();
+    assert_eq!(spec.expression("field1"), "${field1} && ${field1} > 10");
+    assert_eq!(spec.error_text(), "10超の数字で入力してください");
+  }
+
+  #[test]
+  fn test_num_input_spec_negative_and_decimal() {
+    let spec = "-5.5 〜 2.25".parse::<NumInputSpec>().unwrap();
+    assert_eq!(
+      spec.expression("field1"),
+      "${field1} && ${field1} >= -5.5 && ${field1} <= 2.25"
+    );
+    assert!("-10～-1".parse::<NumInputSpec>().is_ok());
+  }
+
+  #[test]
+  fn test_num_input_spec_incorrect() {
+    for s in ["~", "10", "a~90", "90~10", "(10~10]", "1~2~3"] {
+      assert!(matches!(
+        s.parse::<NumInputSpec>(),
+        Err(ConvertError::IncorrectNumInputSpecificationError(_))
+      ));
+    }
+  }
+
+  #[test]
+  fn test_panel_repeat() {
+    let spec = "1〜3".parse::<RepeatSpec>().unwrap();
+    assert_eq!((spec.min, spec.max), (Some(1), Some(3)));
+    let spec = "2～".parse::<RepeatSpec>().unwrap();
+    assert_eq!((spec.min, spec.max), (Some(2), None));
+    for s in ["3~1", "1", "a~3", "1~2~3"] {
+      assert!(matches!(
+        s.parse::<RepeatSpec>(),
+        Err(ConvertError::IncorrectPanelRepeat(_))
+      ));
+    }
+  }
+
+  #[test]
+  fn test_file_size() {
+    assert_eq!("2MB".parse::<FileSize>().unwrap().0, 2 * 1024 * 1024);
+    for s in ["", "MB", "1.5GB", "99999999999999GB"] {
+      assert!(matches!(
+        s.parse::<FileSize>(),
+        Err(ConvertError::IncorrectFileSize(_))
+      ));
+    }
+  }
+
+  #[test]
+  fn test_input_spec() {
+    test_parse_write(PATH_INPUT_INPUT_SPEC_TEST, PATH_OUTPUT_INPUT_SPEC_TEST)
+  }
+
+  #[test]
+  fn test_input_spec_warnings() {
+    let page = parse(PATH_INPUT_INPUT_SPEC_TEST).unwrap();
+    assert_eq!(
+      page.warnings(),
+      vec![
+        ConvertWarning::IneffectiveInputSpec(
+          "field3".to_owned(),
+          "入力指定".to_owned(),
+          "Dropdown".to_owned()
+        ),
+        ConvertWarning::IneffectiveInputSpec(
+          "field3".to_owned(),
+          "数字入力指定範囲".to_owned(),
+          "Dropdown".to_owned()
+        ),
+      ]
+    );
+  }
+  #[test]
+  fn test_check_returns_warnings() {
+    assert_eq!(
+      check(PATH_INPUT_INPUT_SPEC_TEST).unwrap(),
+      parse(PATH_INPUT_INPUT_SPEC_TEST).unwrap().warnings()
+    );
+  }
+
+  #[test]
+  fn test_text_surveyjs() {
+    test_parse_write_target(
+      PATH_INPUT_TEXT_TEST,
+      PATH_OUTPUT_TEXT_SURVEYJS_TEST,
+      OutputTarget::SurveyJs,
+    )
+  }
+
+  #[test]
+  fn test_multiselect_surveyjs() {
+    test_parse_write_target(
+      PATH_INPUT_MULTISELECT_TEST,
+      PATH_OUTPUT_MULTISELECT_SURVEYJS_TEST,
+      OutputTarget::SurveyJs,
+    )
+  }
+
+  #[test]
+  fn test_panel_dynamic_surveyjs() {
+    test_parse_write_target(
+      PATH_INPUT_PANEL_DYNAMIC_TEST,
+      PATH_OUTPUT_PANEL_DYNAMIC_SURVEYJS_TEST,
+      OutputTarget::SurveyJs,
+    )
+  }
+
+  #[test]
+  fn test_text_json_schema() {
+    test_parse_write_target(
+      PATH_INPUT_TEXT_TEST,
+      PATH_OUTPUT_TEXT_JSON_SCHEMA_TEST,
+      OutputTarget::JsonSchema,
+    )
+  }
+
+  #[test]
+  fn test_multiselect_json_schema() {
+    test_parse_write_target(
+      PATH_INPUT_MULTISELECT_TEST,
+      PATH_OUTPUT_MULTISELECT_JSON_SCHEMA_TEST,
+      OutputTarget::JsonSchema,
+    )
+  }
+
+  #[test]
+  fn test_matrix_json_schema() {
+    test_parse_write_target(
+      PATH_INPUT_MATRIX_TEST,
+      PATH_OUTPUT_MATRIX_JSON_SCHEMA_TEST,
+      OutputTarget::JsonSchema,
+    )
+  }
+
+  #[test]
+  fn test_panel_dynamic_json_schema() {
+    test_parse_write_target(
+      PATH_INPUT_PANEL_DYNAMIC_TEST,
+      PATH_OUTPUT_PANEL_DYNAMIC_JSON_SCHEMA_TEST,
+      OutputTarget::JsonSchema,
+    )
+  }
+
+  #[test]
+  fn test_multiselect_typescript() {
+    test_parse_write_target(
+      PATH_INPUT_MULTISELECT_TEST,
+      PATH_OUTPUT_MULTISELECT_TYPESCRIPT_TEST,
+      OutputTarget::TypeScript,
+    )
+  }
+
+  #[test]
+  fn test_matrix_typescript() {
+    test_parse_write_target(
+      PATH_INPUT_MATRIX_TEST,
+      PATH_OUTPUT_MATRIX_TYPESCRIPT_TEST,
+      OutputTarget::TypeScript,
+    )
+  }
+
+  #[test]
+  fn test_panel_dynamic_typescript() {
+    test_parse_write_target(
+      PATH_INPUT_PANEL_DYNAMIC_TEST,
+      PATH_OUTPUT_PANEL_DYNAMIC_TYPESCRIPT_TEST,
+      OutputTarget::TypeScript,
+    )
+  }
+
+  #[test]
+  fn test_description_typescript() {
+    test_parse_write_target(
+      PATH_INPUT_DESCRIPTION_TEST,
+      PATH_OUTPUT_DESCRIPTION_TYPESCRIPT_TEST,
+      OutputTarget::TypeScript,
+    )
+  }
+
+  #[test]
+  fn test_text_html() {
+    test_parse_write_target(
+      PATH_INPUT_TEXT_TEST,
+      PATH_OUTPUT_TEXT_HTML_TEST,
+      OutputTarget::Html,
+    )
+  }
+
+  #[test]
+  fn test_panel_html() {
+    test_parse_write_target(
+      PATH_INPUT_PANEL_TEST,
+      PATH_OUTPUT_PANEL_HTML_TEST,
+      OutputTarget::Html,
+    )
+  }
+
+  #[test]
+  fn test_content_html() {
+    test_parse_write_target(
+      PATH_INPUT_CONTENT_TEST,
+      PATH_OUTPUT_CONTENT_HTML_TEST,
+      OutputTarget::Html,
+    )
+  }
+
+  #[test]
+  fn test_text_markdown() {
+    test_parse_write_target(
+      PATH_INPUT_TEXT_TEST,
+      PATH_OUTPUT_TEXT_MARKDOWN_TEST,
+      OutputTarget::Markdown,
+    )
+  }
+
+  #[test]
+  fn test_panel_dynamic_markdown() {
+    test_parse_write_target(
+      PATH_INPUT_PANEL_DYNAMIC_TEST,
+      PATH_OUTPUT_PANEL_DYNAMIC_MARKDOWN_TEST,
+      OutputTarget::Markdown,
+    )
+  }
+
+  #[test]
+  fn test_multiselect_csv() {
+    test_parse_write_target(
+      PATH_INPUT_MULTISELECT_TEST,
+      PATH_OUTPUT_MULTISELECT_CSV_TEST,
+      OutputTarget::Csv,
+    )
+  }
+
+  #[test]
+  fn test_matrix_csv() {
+    test_parse_write_target(
+      PATH_INPUT_MATRIX_TEST,
+      PATH_OUTPUT_MATRIX_CSV_TEST,
+      OutputTarget::Csv,
+    )
+  }
+
+  #[test]
+  fn test_text_ir() {
+    test_parse_write_target(
+      PATH_INPUT_TEXT_TEST,
+      PATH_OUTPUT_TEXT_IR_TEST,
+      OutputTarget::Ir,
+    )
+  }
+
+  #[test]
+  fn test_input_spec_ir() {
+    test_parse_write_target(
+      PATH_INPUT_INPUT_SPEC_TEST,
+      PATH_OUTPUT_INPUT_SPEC_IR_TEST,
+      OutputTarget::Ir,
+    )
+  }
+
+  #[test]
+  fn test_content_ir() {
+    test_parse_write_target(
+      PATH_INPUT_CONTENT_TEST,
+      PATH_OUTPUT_CONTENT_IR_TEST,
+      OutputTarget::Ir,
+    )
+  }
+
+  #[test]
+  fn test_text_yaml() {
+    let options = OutputOptions {
+      format: OutputFormat::Yaml,
+      ..Default::default()
+    };
+    test_parse_write_options(PATH_INPUT_TEXT_TEST, PATH_OUTPUT_TEXT_YAML_TEST, &options)
+  }
+
+  #[test]
+  fn test_text_compact_json() {
+    let options = OutputOptions {
+      format: OutputFormat::CompactJson,
+      ..Default::default()
+    };
+    test_parse_write_options(
+      PATH_INPUT_TEXT_TEST,
+      PATH_OUTPUT_TEXT_COMPACT_JSON_TEST,
+      &options,
+    )
+  }
+
+  #[test]
+  fn test_text_indent() {
+    let options = OutputOptions {
+      indent: Some(4),
+      ..Default::default()
+    };
+    test_parse_write_options(PATH_INPUT_TEXT_TEST, PATH_OUTPUT_TEXT_INDENT_TEST, &options)
+  }
+
+  #[test]
+  fn test_text_unsupported_format() {
+    let options = OutputOptions {
+      target: OutputTarget::TypeScript,
+      format: OutputFormat::Yaml,
+      ..Default::default()
+    };
+    let page = parse(PATH_INPUT_TEXT_TEST).unwrap();
+    assert!(matches!(
+      writer::write_to_file(&page, PATH_OUTPUT_TEXT_UNSUPPORTED_FORMAT_TEST, &options),
+      Err(ConvertError::UnsupportedFormat(_, _))
+    ));
+  }
+  #[test]
+  fn test_text_unsupported_indent() {
+    let page = parse(PATH_INPUT_TEXT_TEST).unwrap();
+    for (target, format) in [
+      (OutputTarget::Internal, OutputFormat::Yaml),
+      (OutputTarget::Internal, OutputFormat::CompactJson),
+      (OutputTarget::TypeScript, OutputFormat::Json),
+    ] {
+      let options = OutputOptions {
+        target,
+        format,
+        indent: Some(4),
+        ..Default::default()
+      };
+      assert!(matches!(
+        writer::write_to_file(&page, PATH_OUTPUT_TEXT_UNSUPPORTED_FORMAT_TEST, &options),
+        Err(ConvertError::UnsupportedIndent(_))
+      ));
+    }
+  }
+
+  #[test]
+  fn test_no_overwrite() {
+    test_parse_write(PATH_INPUT_TEXT_TEST, PATH_OUTPUT_TEXT_NO_OVERWRITE_TEST);
+    let written = std::fs::read(PATH_OUTPUT_TEXT_NO_OVERWRITE_TEST).unwrap();
+    let options = OutputOptions {
+      target: OutputTarget::Html,
+      no_overwrite: true,
+      ..Default::default()
+    };
+    let page = parse(PATH_INPUT_TEXT_TEST).unwrap();
+    assert!(matches!(
+      writer::write_to_file(&page, PATH_OUTPUT_TEXT_NO_OVERWRITE_TEST, &options),
+      Err(ConvertError::OutputExists(_))
+    ));
+    assert_eq!(
+      std::fs::read(PATH_OUTPUT_TEXT_NO_OVERWRITE_TEST).unwrap(),
+      written
+    );
+  }
+
+  #[test]
+  fn test_no_overwrite_new_file() {
+    let dir = std::env::temp_dir().join(format!("converter_no_overwrite_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let output_path = dir.join("test_text_output.json");
+    let page = parse(PATH_INPUT_TEXT_TEST).unwrap();
+    let options = OutputOptions {
+      no_overwrite: true,
+      ..Default::default()
+    };
+    let first = writer::write_to_file(&page, output_path.to_str().unwrap(), &options);
+    let second = writer::write_to_file(&page, output_path.to_str().unwrap(), &options);
+    let entries = std::fs::read_dir(&dir).unwrap().count();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(first.is_ok());
+    assert!(matches!(second, Err(ConvertError::OutputExists(_))));
+    // The temporary files of both writes are gone
+    assert_eq!(entries, 1);
+  }
+
+  #[test]
+  fn test_no_overwrite_copy() {
+    let dir = std::env::temp_dir().join(format!("converter_copy_new_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let (temp_path, output_path) = (dir.join("output.tmp"), dir.join("output.json"));
+    std::fs::write(&temp_path, "new").unwrap();
+    let first = writer::copy_new(&temp_path, &output_path);
+    std::fs::write(&temp_path, "newer").unwrap();
+    let second = writer::copy_new(&temp_path, &output_path);
+    let copied = std::fs::read_to_string(&output_path).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(first.is_ok());
+    assert!(matches!(second, Err(ConvertError::OutputExists(_))));
+    assert_eq!(copied, "new");
+  }
+
+  #[test]
+  fn test_create_parent_dirs() {
+    let dir = std::env::temp_dir().join(format!("converter_test_{}", std::process::id()));
+    let output_path = dir.join("nested/test_text_output.json");
+    let page = parse(PATH_INPUT_TEXT_TEST).unwrap();
+    let options = OutputOptions::default();
+    assert!(writer::write_to_file(&page, output_path.to_str().unwrap(), &options).is_err());
+    let options = OutputOptions {
+      create_parent_dirs: true,
+      ..Default::default()
+    };
+    let write_res = writer::write_to_file(&page, output_path.to_str().unwrap(), &options);
+    let entries = std::fs::read_dir(output_path.parent().unwrap())
+      .unwrap()
+      .count();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(write_res.is_ok());
+    // Only the output itself, the temporary file was renamed
+    assert_eq!(entries, 1);
+  }
+
+  fn assert_same_page(path: &'static str, xlsx_path: &'static str) {
+    let page = parse(path).unwrap();
+    let xlsx_page = parse(xlsx_path).unwrap();
+    assert_eq!(
+      serde_json::to_value(&page).unwrap(),
+      serde_json::to_value(&xlsx_page).unwrap()
+    );
+  }
+
+  #[test]
+  fn test_text_ods() {
+    assert_same_page(PATH_INPUT_TEXT_ODS_TEST, PATH_INPUT_TEXT_TEST)
+  }
+
+  #[test]
+  fn test_panel_ods() {
+    assert_same_page(PATH_INPUT_PANEL_ODS_TEST, PATH_INPUT_PANEL_TEST)
+  }
+
+  #[test]
+  fn test_text_xls() {
+    assert_same_page(PATH_INPUT_TEXT_XLS_TEST, PATH_INPUT_TEXT_TEST)
+  }
+
+  #[test]
+  fn test_text_xlsb() {
+    assert_same_page(PATH_INPUT_TEXT_XLSB_TEST, PATH_INPUT_TEXT_TEST)
+  }
+
+  #[test]
+  fn test_corrupt_xlsx() {
+    let parse_res = parse(PATH_INPUT_CORRUPT_XLSX_TEST);
+    assert!(matches!(parse_res, Err(ConvertError::XlsxError(_))));
+  }
+
+  #[test]
+  fn test_unsupported_format() {
+    let parse_res = parse(PATH_INPUT_UNSUPPORTED_FORMAT_TEST);
+    assert!(matches!(
+      parse_res,
+      Err(ConvertError::UnsupportedWorkbook(_))
+    ));
+  }
+
+  #[test]
+  fn test_panel_required_later() {
+    test_parse_write(
+      PATH_INPUT_PANEL_REQUIRED_LATER_TEST,
+      PATH_OUTPUT_PANEL_REQUIRED_LATER_TEST,
+    )
+  }
+
+  #[test]
+  fn test_calculated_surveyjs() {
+    test_parse_write_target(
+      PATH_INPUT_CALCULATED_TEST,
+      PATH_OUTPUT_CALCULATED_SURVEYJS_TEST,
+      OutputTarget::SurveyJs,
+    )
+  }
+
+  #[test]
+  fn test_visible_condition() {
+    test_parse_write(
+      PATH_INPUT_VISIBLE_CONDITION_TEST,
+      PATH_OUTPUT_VISIBLE_CONDITION_TEST,
+    )
+  }
+
+  #[test]
+  fn test_visible_condition_surveyjs() {
+    test_parse_write_target(
+      PATH_INPUT_VISIBLE_CONDITION_TEST,
+      PATH_OUTPUT_VISIBLE_CONDITION_SURVEYJS_TEST,
+      OutputTarget::SurveyJs,
+    )
+  }
+
+  #[test]
+  fn test_visible_condition_unsupported() {
+    let options = OutputOptions {
+      target: OutputTarget::SurveyJs,
+      ..Default::default()
+    };
+    let page = parse(PATH_INPUT_VISIBLE_CONDITION_UNSUPPORTED_TEST).unwrap();
+    assert!(matches!(
+      writer::write_to_file(
+        &page,
+        PATH_OUTPUT_VISIBLE_CONDITION_UNSUPPORTED_TEST,
+        &options
+      ),
+      Err(ConvertError::UnsupportedExpression(_))
+    ));
+  }
+
+  #[test]
+  fn test_rating_html() {
+    test_parse_write_target(
+      PATH_INPUT_RATING_TEST,
+      PATH_OUTPUT_RATING_HTML_TEST,
+      OutputTarget::Html,
+    )
+  }
 }
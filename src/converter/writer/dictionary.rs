@@ -0,0 +1,127 @@
+use super::{OutputOptions, OutputWriter};
+use crate::converter::error::Result;
+use crate::converter::field::element::Element;
+use crate::converter::field::subtypes::OptionType;
+use crate::converter::field::{Field, Page};
+use std::io::Write;
+
+const COLUMNS: [&str; 15] = [
+  "QuestionKey",
+  "Group",
+  "Type",
+  "Label",
+  "Required",
+  "Options",
+  "Rows",
+  "OptionsFromKey",
+  "Min",
+  "Max",
+  "MaxChars",
+  "InputSpec",
+  "NumInputSpec",
+  "Validators",
+  "Visible",
+];
+
+// Codebook of every question as a Markdown table
+pub(super) struct MarkdownWriter;
+
+// Codebook of every question as CSV, with a BOM so spreadsheet software detects UTF-8
+pub(super) struct CsvWriter;
+
+impl OutputWriter for MarkdownWriter {
+  fn write(&self, page: &Page, _options: &OutputOptions, out: &mut dyn Write) -> Result<()> {
+    if let Some(ref title) = page.metadata().title {
+      writeln!(out, "# {}", title)?;
+      writeln!(out)?;
+    }
+    writeln!(out, "| {} |", COLUMNS.join(" | "))?;
+    writeln!(out, "|{}", " --- |".repeat(COLUMNS.len()))?;
+    for row in rows(page) {
+      let cells = row
+        .iter()
+        .map(|c| markdown_cell(c))
+        .collect::<Vec<String>>();
+      writeln!(out, "| {} |", cells.join(" | "))?;
+    }
+    Ok(())
+  }
+}
+
+impl OutputWriter for CsvWriter {
+  fn write(&self, page: &Page, _options: &OutputOptions, out: &mut dyn Write) -> Result<()> {
+    write!(out, "\u{feff}")?;
+    write!(out, "{}\r\n", COLUMNS.join(","))?;
+    for row in rows(page) {
+      let cells = row.iter().map(|c| csv_cell(c)).collect::<Vec<String>>();
+      write!(out, "{}\r\n", cells.join(","))?;
+    }
+    Ok(())
+  }
+}
+
+fn rows(page: &Page) -> Vec<Vec<String>> {
+  let mut rows = Vec::<Vec<String>>::new();
+  for element in page.elements().iter() {
+    match element {
+      Element::Field(f) => rows.push(row(f, "")),
+      Element::Panel(p) => rows.extend(p.fields().iter().map(|f| row(f, p.title()))),
+      Element::Content(_) => (),
+    }
+  }
+  rows
+}
+
+fn row(field: &Field, group: &str) -> Vec<String> {
+  let optional = |value: Option<String>| value.unwrap_or_default();
+  vec![
+    field.field_name().to_owned(),
+    group.to_owned(),
+    serde_json::to_value(field.variant())
+      .ok()
+      .and_then(|v| v.as_str().map(|s| s.to_owned()))
+      .unwrap_or_default(),
+    field.label().to_owned(),
+    field.is_required().to_string(),
+    optional(field.options().map(option_list)),
+    optional(field.matrix_rows().map(option_list)),
+    optional(field.options_from_key().map(|s| s.to_owned())),
+    optional(field.min().map(|n| n.to_string())),
+    optional(field.max().map(|n| n.to_string())),
+    optional(field.max_chars().map(|n| n.to_string())),
+    optional(field.input_spec().map(|s| s.to_string())),
+    optional(field.num_input_spec().map(|s| s.to_string())),
+    optional(field.processed().validators.as_ref().map(|vlds| {
+      vlds
+        .iter()
+        .map(|vld| vld.text.to_owned())
+        .collect::<Vec<String>>()
+        .join("\n")
+    })),
+    optional(field.processed().visible.to_owned()),
+  ]
+}
+
+fn option_list(options: &[OptionType]) -> String {
+  options
+    .iter()
+    .map(|opt| format!("{}: {}", opt.value, opt.label))
+    .collect::<Vec<String>>()
+    .join("\n")
+}
+
+fn markdown_cell(cell: &str) -> String {
+  cell
+    .replace('\\', "\\\\")
+    .replace('|', "\\|")
+    .replace("\r\n", "\n")
+    .replace('\n', "<br>")
+}
+
+fn csv_cell(cell: &str) -> String {
+  if cell.contains(&[',', '"', '\n', '\r'][..]) {
+    format!("\"{}\"", cell.replace('"', "\"\""))
+  } else {
+    cell.to_owned()
+  }
+}
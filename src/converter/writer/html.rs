@@ -0,0 +1,435 @@
+use super::{OutputOptions, OutputWriter};
+use crate::converter::error::Result;
+use crate::converter::field::element::{escape_html, Content, Element, Panel, PanelVariant};
+use crate::converter::field::subtypes::{FieldVariant, OptionType, Validator};
+use crate::converter::field::{Field, Page};
+use serde_json::Value;
+use std::io::Write;
+
+const STYLE: &str = "body{font-family:sans-serif;margin:0;background:#f4f5f7;color:#222}\
+main{max-width:760px;margin:0 auto;padding:24px}\
+header,section.question,section.content,fieldset,footer{background:#fff;border:1px solid #ddd;border-radius:6px;padding:16px;margin:0 0 16px}\
+fieldset{border-color:#99a}\
+legend{font-weight:bold;padding:0 4px}\
+.key{font-family:monospace;font-size:12px;color:#667}\
+.label{display:block;font-weight:bold;margin:4px 0 8px}\
+.required{color:#c00;margin-left:4px}\
+.description{color:#555;white-space:pre-wrap}\
+.note{font-size:13px;color:#446;margin:8px 0 0}\
+.rules{font-size:13px;color:#933;margin:8px 0 0;padding-left:20px}\
+code{background:#eef;padding:0 4px;border-radius:3px}\
+input[type=text],textarea,select{width:100%;box-sizing:border-box;padding:6px}\
+table{border-collapse:collapse}td,th{border:1px solid #ccc;padding:4px 8px}\
+label.choice{display:block}";
+
+// A 0 to 10 scale is the longest one shown as a row of buttons
+const MAX_RATING_BUTTONS: u64 = 11;
+
+// A self-contained page showing how the form will look, with its rules written out
+pub(super) struct HtmlWriter;
+
+impl OutputWriter for HtmlWriter {
+  fn write(&self, page: &Page, _options: &OutputOptions, out: &mut dyn Write) -> Result<()> {
+    let mut html = HtmlBuilder::default();
+    html.page(page);
+    out.write_all(html.0.as_bytes())?;
+    Ok(())
+  }
+}
+
+#[derive(Default)]
+struct HtmlBuilder(String);
+
+impl HtmlBuilder {
+  fn line(&mut self, line: &str) {
+    self.0.push_str(line);
+    self.0.push('\n');
+  }
+
+  fn page(&mut self, page: &Page) {
+    let metadata = page.metadata();
+    let title = metadata.title.as_deref().unwrap_or("フォームプレビュー");
+    self.line("<!DOCTYPE html>");
+    self.line("<html lang=\"ja\">");
+    self.line("<head>");
+    self.line("<meta charset=\"utf-8\">");
+    self.line(&format!("<title>{}</title>", escape_html(title)));
+    self.line(&format!("<style>{}</style>", STYLE));
+    self.line("</head>");
+    self.line("<body>");
+    self.line("<main>");
+    self.line("<header>");
+    self.line(&format!("<h1>{}</h1>", escape_html(title)));
+    if let Some(ref desc) = metadata.description {
+      self.line(&format!(
+        "<p class=\"description\">{}</p>",
+        escape_html(desc)
+      ));
+    }
+    let mut settings = Vec::<String>::new();
+    if let Some(ref id) = metadata.form_id {
+      settings.push(format!("フォームID: {}", escape_html(id)));
+    }
+    if let Some(ref version) = metadata.version {
+      settings.push(format!("バージョン: {}", escape_html(version)));
+    }
+    if !settings.is_empty() {
+      self.line(&format!("<p class=\"note\">{}</p>", settings.join(" / ")));
+    }
+    self.line("</header>");
+    self.line("<form>");
+    self.elements(page.elements());
+    self.line("</form>");
+    if let Some(ref completion) = metadata.completion_text {
+      self.line("<footer>");
+      self.line("<p class=\"key\">完了メッセージ</p>");
+      self.line(&format!(
+        "<p class=\"description\">{}</p>",
+        escape_html(completion)
+      ));
+      self.line("</footer>");
+    }
+    self.line("</main>");
+    self.line("</body>");
+    self.line("</html>");
+  }
+
+  fn elements(&mut self, elements: &[Element]) {
+    for element in elements.iter() {
+      match element {
+        Element::Field(f) => self.field(f),
+        Element::Panel(p) => self.panel(p),
+        Element::Content(c) => self.content(c),
+      }
+    }
+  }
+
+  fn panel(&mut self, panel: &Panel) {
+    self.line(&format!("<fieldset id=\"{}\">", escape_html(panel.name())));
+    let required = if panel.is_required() {
+      "<span class=\"required\">必須</span>"
+    } else {
+      ""
+    };
+    self.line(&format!(
+      "<legend>{}{}</legend>",
+      escape_html(panel.title()),
+      required
+    ));
+    self.line(&format!(
+      "<p class=\"key\">{}</p>",
+      escape_html(panel.name())
+    ));
+    if let PanelVariant::Dynamic = panel.variant() {
+      let count = |c: Option<u64>| c.map(|n| n.to_string()).unwrap_or_default();
+      self.line(&format!(
+        "<p class=\"note\">繰り返し: {}〜{}件</p>",
+        count(panel.min_count()),
+        count(panel.max_count())
+      ));
+    }
+    self.visibility(panel.visible());
+    self.elements(panel.elements());
+    self.line("</fieldset>");
+  }
+
+  fn content(&mut self, content: &Content) {
+    self.line(&format!(
+      "<section class=\"content\" id=\"{}\">",
+      escape_html(content.name())
+    ));
+    self.line(&format!(
+      "<p class=\"key\">{}</p>",
+      escape_html(content.name())
+    ));
+    self.line(content.html());
+    self.visibility(content.visible());
+    self.line("</section>");
+  }
+
+  fn field(&mut self, field: &Field) {
+    let name = escape_html(field.field_name());
+    let processed = field.processed();
+    self.line(&format!("<section class=\"question\" id=\"{}\">", name));
+    self.line(&format!(
+      "<p class=\"key\">{} · {}</p>",
+      name,
+      variant_name(field.variant())
+    ));
+    let required = if field.is_required() {
+      "<span class=\"required\">必須</span>"
+    } else {
+      ""
+    };
+    self.line(&format!(
+      "<span class=\"label\">{}{}</span>",
+      escape_html(field.label()),
+      required
+    ));
+    if let Some(desc) = field.description() {
+      self.line(&format!(
+        "<p class=\"description\">{}</p>",
+        escape_html(desc)
+      ));
+    }
+    self.control(field);
+    if let Some(opt_from_key) = field.options_from_key() {
+      self.line(&format!(
+        "<p class=\"note\">選択肢: <code>{}</code> の回答</p>",
+        escape_html(opt_from_key)
+      ));
+    }
+    if let Some(ref default) = processed.default_value {
+      self.line(&format!(
+        "<p class=\"note\">初期値: <code>{}</code></p>",
+        escape_html(&default.to_string())
+      ));
+    }
+    if let Some(ref expression) = processed.expression {
+      self.line(&format!(
+        "<p class=\"note\">計算式: <code>{}</code></p>",
+        escape_html(expression)
+      ));
+    }
+    self.validators(&processed.validators);
+    self.visibility(processed.visible.as_deref());
+    self.line("</section>");
+  }
+
+  fn control(&mut self, field: &Field) {
+    let name = escape_html(field.field_name());
+    let processed = field.processed();
+    let options = field.options().unwrap_or_default();
+    let default = processed.default_value.as_ref();
+    match field.variant() {
+      FieldVariant::Text => {
+        let mut attributes = String::new();
+        if let Some(ref plc) = processed.placeholder {
+          attributes.push_str(&format!(" placeholder=\"{}\"", escape_html(plc)));
+        }
+        if let Some(mx) = processed.max_length {
+          attributes.push_str(&format!(" maxlength=\"{}\"", mx));
+        }
+        if let Some(Value::String(s)) = default {
+          attributes.push_str(&format!(" value=\"{}\"", escape_html(s)));
+        }
+        self.line(&format!(
+          "<input type=\"text\" name=\"{}\"{}>",
+          name, attributes
+        ));
+      }
+      FieldVariant::TextArea => {
+        let mut attributes = String::new();
+        if let Some(ref plc) = processed.placeholder {
+          attributes.push_str(&format!(" placeholder=\"{}\"", escape_html(plc)));
+        }
+        if let Some(mx) = processed.max_length {
+          attributes.push_str(&format!(" maxlength=\"{}\"", mx));
+        }
+        let text = match default {
+          Some(Value::String(s)) => escape_html(s),
+          _ => "".to_owned(),
+        };
+        self.line(&format!(
+          "<textarea name=\"{}\" rows=\"4\"{}>{}</textarea>",
+          name, attributes, text
+        ));
+      }
+      FieldVariant::Dropdown => {
+        self.line(&format!("<select name=\"{}\">", name));
+        let caption = processed.options_caption.as_deref().unwrap_or("");
+        self.line(&format!(
+          "<option value=\"\">{}</option>",
+          escape_html(caption)
+        ));
+        for opt in options.iter() {
+          self.line(&format!(
+            "<option value=\"{}\"{}>{}</option>",
+            escape_html(&opt.value),
+            selected(default, opt, " selected"),
+            escape_html(&opt.label)
+          ));
+        }
+        self.line("</select>");
+      }
+      FieldVariant::Radio | FieldVariant::Multiselect => {
+        let input_type = match field.variant() {
+          FieldVariant::Radio => "radio",
+          _ => "checkbox",
+        };
+        for opt in options.iter() {
+          self.line(&format!(
+            "<label class=\"choice\"><input type=\"{}\" name=\"{}\" value=\"{}\"{}> {}</label>",
+            input_type,
+            name,
+            escape_html(&opt.value),
+            selected(default, opt, " checked"),
+            escape_html(&opt.label)
+          ));
+        }
+      }
+      FieldVariant::Ranking => {
+        self.line("<ol>");
+        for opt in options.iter() {
+          self.line(&format!("<li>{}</li>", escape_html(&opt.label)));
+        }
+        self.line("</ol>");
+      }
+      FieldVariant::Matrix => {
+        self.line("<table>");
+        let mut header = "<tr><th></th>".to_owned();
+        for opt in options.iter() {
+          header.push_str(&format!("<th>{}</th>", escape_html(&opt.label)));
+        }
+        header.push_str("</tr>");
+        self.line(&header);
+        for row in field.matrix_rows().unwrap_or_default() {
+          let row_default = default.and_then(|d| d.get(&row.value));
+          let mut line = format!("<tr><th>{}</th>", escape_html(&row.label));
+          for opt in options.iter() {
+            line.push_str(&format!(
+              "<td><input type=\"radio\" name=\"{}\" value=\"{}\"{}></td>",
+              escape_html(&row.value),
+              escape_html(&opt.value),
+              selected(row_default, opt, " checked")
+            ));
+          }
+          line.push_str("</tr>");
+          self.line(&line);
+        }
+        self.line("</table>");
+      }
+      FieldVariant::Rating => {
+        let rating = processed.rating.as_ref();
+        let min = rating.and_then(|r| r.min).unwrap_or(1);
+        let max = rating.and_then(|r| r.max).unwrap_or(5);
+        let step = rating.and_then(|r| r.step).unwrap_or(1).max(1);
+        let mut line = String::new();
+        if let Some(lbl) = rating.and_then(|r| r.min_label.as_deref()) {
+          line.push_str(&format!("{} ", escape_html(lbl)));
+        }
+        let rate = match default {
+          Some(Value::Number(n)) => n.as_u64(),
+          _ => None,
+        };
+        if max.saturating_sub(min) / step < MAX_RATING_BUTTONS {
+          for value in (min..=max).step_by(step as usize) {
+            let checked = if rate == Some(value) { " checked" } else { "" };
+            line.push_str(&format!(
+              "<label><input type=\"radio\" name=\"{}\" value=\"{}\"{}>{}</label> ",
+              name, value, checked, value
+            ));
+          }
+        } else {
+          // Too many points for a row of buttons
+          let value = rate
+            .map(|r| format!(" value=\"{}\"", r))
+            .unwrap_or_default();
+          line.push_str(&format!(
+            "<input type=\"number\" name=\"{}\" min=\"{}\" max=\"{}\" step=\"{}\"{}> ",
+            name, min, max, step, value
+          ));
+        }
+        if let Some(lbl) = rating.and_then(|r| r.max_label.as_deref()) {
+          line.push_str(&escape_html(lbl));
+        }
+        self.line(line.trim_end());
+      }
+      FieldVariant::Boolean => {
+        let checked = match default {
+          Some(Value::Bool(true)) => " checked",
+          _ => "",
+        };
+        let mut labels = Vec::<String>::new();
+        if let Some(ref lbl) = processed.label_true {
+          labels.push(format!("はい: {}", escape_html(lbl)));
+        }
+        if let Some(ref lbl) = processed.label_false {
+          labels.push(format!("いいえ: {}", escape_html(lbl)));
+        }
+        self.line(&format!(
+          "<label class=\"choice\"><input type=\"checkbox\" name=\"{}\"{}> {}</label>",
+          name,
+          checked,
+          labels.join(" / ")
+        ));
+      }
+      FieldVariant::File => {
+        let mut attributes = String::new();
+        if let Some(ref file) = processed.file {
+          if let Some(ref types) = file.accepted_types {
+            attributes.push_str(&format!(" accept=\"{}\"", escape_html(types)));
+          }
+          if file.allow_multiple {
+            attributes.push_str(" multiple");
+          }
+          if let Some(size) = file.max_size {
+            self.line(&format!(
+              "<p class=\"note\">最大ファイルサイズ: {} bytes</p>",
+              size
+            ));
+          }
+        }
+        self.line(&format!(
+          "<input type=\"file\" name=\"{}\"{}>",
+          name, attributes
+        ));
+      }
+      FieldVariant::Hidden => {
+        self.line("<p class=\"note\">回答者には表示されません</p>");
+      }
+      FieldVariant::Calculated => {
+        self.line(&format!("<output name=\"{}\"></output>", name));
+      }
+    }
+  }
+
+  fn validators(&mut self, validators: &Option<Vec<Validator>>) {
+    let validators = match validators {
+      Some(vlds) if !vlds.is_empty() => vlds,
+      _ => return,
+    };
+    self.line("<ul class=\"rules\">");
+    for vld in validators.iter() {
+      let expression = match vld.expression {
+        Some(ref expr) => format!(" <code>{}</code>", escape_html(expr)),
+        None => "".to_owned(),
+      };
+      self.line(&format!(
+        "<li>{}{}</li>",
+        escape_html(&vld.text),
+        expression
+      ));
+    }
+    self.line("</ul>");
+  }
+
+  fn visibility(&mut self, visible: Option<&str>) {
+    if let Some(expr) = visible {
+      self.line(&format!(
+        "<p class=\"note\">表示条件: <code>{}</code></p>",
+        escape_html(expr)
+      ));
+    }
+  }
+}
+
+fn selected(default: Option<&Value>, option: &OptionType, attribute: &'static str) -> &'static str {
+  let is_default = match default {
+    Some(Value::String(s)) => *s == option.value,
+    Some(Value::Array(values)) => values.iter().any(|v| v.as_str() == Some(option.value())),
+    _ => false,
+  };
+  if is_default {
+    attribute
+  } else {
+    ""
+  }
+}
+
+fn variant_name(variant: &FieldVariant) -> String {
+  serde_json::to_value(variant)
+    .ok()
+    .and_then(|v| v.as_str().map(|s| s.to_owned()))
+    .unwrap_or_default()
+}
@@ -0,0 +1,14 @@
+use super::{serialize, OutputOptions, OutputWriter};
+use crate::converter::error::Result;
+use crate::converter::field::Page;
+use std::io::Write;
+
+// The PascalCase format our wrapper maps to SurveyJS
+pub(super) struct InternalWriter;
+
+impl OutputWriter for InternalWriter {
+  fn write(&self, page: &Page, options: &OutputOptions, out: &mut dyn Write) -> Result<()> {
+    serialize(page, options, out)?;
+    Ok(())
+  }
+}
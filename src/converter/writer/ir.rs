@@ -0,0 +1,180 @@
+use super::{serialize, OutputOptions, OutputWriter};
+use crate::converter::error::Result;
+use crate::converter::field::element::{Content, Element, Panel, PanelVariant};
+use crate::converter::field::source::SourcePosition;
+use crate::converter::field::subtypes::{
+  FieldVariant, InputSpec, NumInputSpec, OptionType, ProcessedData,
+};
+use crate::converter::field::{Field, Page};
+use crate::converter::metadata::FormMetadata;
+use serde::Serialize;
+use std::io::Write;
+
+// Everything the reader understood, including the raw values other outputs leave out
+pub(super) struct IrWriter;
+
+impl OutputWriter for IrWriter {
+  fn write(&self, page: &Page, options: &OutputOptions, out: &mut dyn Write) -> Result<()> {
+    serialize(&IrPage::from_page(page), options, out)?;
+    Ok(())
+  }
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct IrPage<'a> {
+  metadata: &'a FormMetadata,
+  warnings: Vec<String>,
+  elements: Vec<IrElement<'a>>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(tag = "Kind")]
+enum IrElement<'a> {
+  Field(Box<IrField<'a>>),
+  Panel(IrPanel<'a>),
+  Content(IrContent<'a>),
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct IrField<'a> {
+  field_name: &'a str,
+  is_required: bool,
+  variant: &'a FieldVariant,
+  label: &'a str,
+  description: Option<&'a str>,
+  placeholder_text: Option<&'a str>,
+  input_spec: Option<&'a InputSpec>,
+  num_input_spec: Option<&'a NumInputSpec>,
+  num_input_spec_error: Option<&'a str>,
+  options: Option<&'a [OptionType]>,
+  options_from_key: Option<&'a str>,
+  matrix_rows: Option<&'a [OptionType]>,
+  all_rows_required: Option<bool>,
+  default_value: Option<&'a str>,
+  max: Option<u64>,
+  min: Option<u64>,
+  max_chars: Option<u64>,
+  step: Option<u64>,
+  max_label: Option<&'a str>,
+  min_label: Option<&'a str>,
+  true_label: Option<&'a str>,
+  false_label: Option<&'a str>,
+  calculation: Option<&'a str>,
+  visible_condition: Option<&'a str>,
+  file_types: Option<&'a [String]>,
+  max_file_size: Option<u64>,
+  display_condition_first: Option<&'a [String]>,
+  display_condition_second: Option<&'a [String]>,
+  display_condition_third: Option<&'a [String]>,
+  processed: &'a ProcessedData,
+  source: Option<&'a SourcePosition>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct IrPanel<'a> {
+  name: &'a str,
+  is_required: bool,
+  variant: &'a PanelVariant,
+  title: &'a str,
+  visible: Option<&'a str>,
+  min_count: Option<u64>,
+  max_count: Option<u64>,
+  elements: Vec<IrElement<'a>>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct IrContent<'a> {
+  name: &'a str,
+  html: &'a str,
+  visible: Option<&'a str>,
+  source: Option<&'a SourcePosition>,
+}
+
+impl<'a> IrPage<'a> {
+  fn from_page(page: &'a Page) -> Self {
+    IrPage {
+      metadata: page.metadata(),
+      warnings: page.warnings().iter().map(|w| w.to_string()).collect(),
+      elements: ir_elements(page.elements()),
+    }
+  }
+}
+
+fn ir_elements(elements: &[Element]) -> Vec<IrElement<'_>> {
+  elements
+    .iter()
+    .map(|element| match element {
+      Element::Field(f) => IrElement::Field(Box::new(IrField::from_field(f))),
+      Element::Panel(p) => IrElement::Panel(IrPanel::from_panel(p)),
+      Element::Content(c) => IrElement::Content(IrContent::from_content(c)),
+    })
+    .collect()
+}
+
+impl<'a> IrField<'a> {
+  fn from_field(field: &'a Field) -> Self {
+    IrField {
+      field_name: field.field_name(),
+      is_required: field.is_required(),
+      variant: field.variant(),
+      label: field.label(),
+      description: field.description(),
+      placeholder_text: field.placeholder_text(),
+      input_spec: field.input_spec(),
+      num_input_spec: field.num_input_spec(),
+      num_input_spec_error: field.num_input_spec_error(),
+      options: field.options(),
+      options_from_key: field.options_from_key(),
+      matrix_rows: field.matrix_rows(),
+      all_rows_required: field.all_rows_required(),
+      default_value: field.default_value(),
+      max: field.max(),
+      min: field.min(),
+      max_chars: field.max_chars(),
+      step: field.step(),
+      max_label: field.max_label(),
+      min_label: field.min_label(),
+      true_label: field.true_label(),
+      false_label: field.false_label(),
+      calculation: field.calculation(),
+      visible_condition: field.visible_condition(),
+      file_types: field.file_types(),
+      max_file_size: field.max_file_size(),
+      display_condition_first: field.display_condition_first(),
+      display_condition_second: field.display_condition_second(),
+      display_condition_third: field.display_condition_third(),
+      processed: field.processed(),
+      source: field.source(),
+    }
+  }
+}
+
+impl<'a> IrPanel<'a> {
+  fn from_panel(panel: &'a Panel) -> Self {
+    IrPanel {
+      name: panel.name(),
+      is_required: panel.is_required(),
+      variant: panel.variant(),
+      title: panel.title(),
+      visible: panel.visible(),
+      min_count: panel.min_count(),
+      max_count: panel.max_count(),
+      elements: ir_elements(panel.elements()),
+    }
+  }
+}
+
+impl<'a> IrContent<'a> {
+  fn from_content(content: &'a Content) -> Self {
+    IrContent {
+      name: content.name(),
+      html: content.html(),
+      visible: content.visible(),
+      source: content.source(),
+    }
+  }
+}
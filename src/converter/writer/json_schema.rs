@@ -0,0 +1,308 @@
+use super::{serialize, OutputOptions, OutputWriter};
+use crate::converter::error::Result;
+use crate::converter::field::element::{Element, Panel, PanelVariant};
+use crate::converter::field::subtypes::{ExpressionRule, FieldVariant, OptionType, ValidatorType};
+use crate::converter::field::{Field, Page};
+use serde::ser::{SerializeMap, Serializer};
+use serde::Serialize;
+use serde_json::Value;
+use std::io::Write;
+
+const SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+// JSON Schema of the answers object a filled in form produces
+pub(super) struct JsonSchemaWriter;
+
+impl OutputWriter for JsonSchemaWriter {
+  fn write(&self, page: &Page, options: &OutputOptions, out: &mut dyn Write) -> Result<()> {
+    serialize(&Schema::from_page(page), options, out)?;
+    Ok(())
+  }
+}
+
+#[derive(Serialize, Debug, Default)]
+struct Schema<'a> {
+  #[serde(rename = "$schema", skip_serializing_if = "Option::is_none")]
+  dialect: Option<&'static str>,
+
+  #[serde(rename = "$id", skip_serializing_if = "Option::is_none")]
+  id: Option<&'a str>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  title: Option<&'a str>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  description: Option<&'a str>,
+
+  #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+  schema_type: Option<&'static str>,
+
+  #[serde(rename = "const", skip_serializing_if = "Option::is_none")]
+  constant: Option<bool>,
+
+  #[serde(rename = "enum", skip_serializing_if = "Option::is_none")]
+  enumeration: Option<Vec<&'a str>>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  default: Option<&'a Value>,
+
+  #[serde(rename = "minLength", skip_serializing_if = "Option::is_none")]
+  min_length: Option<u64>,
+
+  #[serde(rename = "maxLength", skip_serializing_if = "Option::is_none")]
+  max_length: Option<u64>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pattern: Option<&'a str>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  minimum: Option<u64>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  maximum: Option<u64>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  items: Option<Box<Schema<'a>>>,
+
+  #[serde(rename = "uniqueItems", skip_serializing_if = "Option::is_none")]
+  unique_items: Option<bool>,
+
+  #[serde(rename = "minItems", skip_serializing_if = "Option::is_none")]
+  min_items: Option<u64>,
+
+  #[serde(rename = "maxItems", skip_serializing_if = "Option::is_none")]
+  max_items: Option<u64>,
+
+  #[serde(rename = "minProperties", skip_serializing_if = "Option::is_none")]
+  min_properties: Option<u64>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  properties: Option<Properties<'a>>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  required: Option<Vec<&'a str>>,
+
+  #[serde(
+    rename = "additionalProperties",
+    skip_serializing_if = "Option::is_none"
+  )]
+  additional_properties: Option<bool>,
+}
+
+// Properties keep the order of the questions in the spreadsheet
+#[derive(Debug, Default)]
+struct Properties<'a>(Vec<(&'a str, Schema<'a>)>);
+
+impl<'a> Serialize for Properties<'a> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    let mut map = serializer.serialize_map(Some(self.0.len()))?;
+    for (key, schema) in self.0.iter() {
+      map.serialize_entry(key, schema)?;
+    }
+    map.end()
+  }
+}
+
+impl<'a> Schema<'a> {
+  fn from_page(page: &'a Page) -> Self {
+    let metadata = page.metadata();
+    let mut schema = Schema::object(page.elements());
+    schema.dialect = Some(SCHEMA_DIALECT);
+    schema.id = metadata.form_id.as_deref();
+    schema.title = metadata.title.as_deref();
+    schema.description = metadata.description.as_deref();
+    schema
+  }
+
+  // Fields of a plain panel are answered at the level of the panel itself
+  fn object(elements: &'a [Element]) -> Self {
+    let mut properties = Properties::default();
+    let mut required = Vec::<&str>::new();
+    Self::collect_properties(elements, &mut properties, &mut required);
+    Schema {
+      schema_type: Some("object"),
+      properties: Some(properties),
+      required: Some(required),
+      additional_properties: Some(false),
+      ..Default::default()
+    }
+  }
+
+  fn collect_properties(
+    elements: &'a [Element],
+    properties: &mut Properties<'a>,
+    required: &mut Vec<&'a str>,
+  ) {
+    for element in elements.iter() {
+      match element {
+        Element::Field(f) => {
+          if f.is_required() {
+            required.push(f.field_name());
+          }
+          properties.0.push((f.field_name(), Schema::from_field(f)));
+        }
+        Element::Panel(p) => match p.variant() {
+          PanelVariant::Panel => Self::collect_properties(p.elements(), properties, required),
+          PanelVariant::Dynamic => {
+            if p.is_required() {
+              required.push(p.name());
+            }
+            properties.0.push((p.name(), Schema::from_dynamic_panel(p)));
+          }
+        },
+        Element::Content(_) => (),
+      }
+    }
+  }
+
+  fn from_dynamic_panel(panel: &'a Panel) -> Self {
+    let min_items = match (panel.min_count(), panel.is_required()) {
+      (None, true) => Some(1),
+      (min, _) => min,
+    };
+    Schema {
+      title: Some(panel.title()),
+      schema_type: Some("array"),
+      items: Some(Box::new(Schema::object(panel.elements()))),
+      min_items,
+      max_items: panel.max_count(),
+      ..Default::default()
+    }
+  }
+
+  fn from_field(field: &'a Field) -> Self {
+    let processed = field.processed();
+    let mut schema = Schema {
+      title: Some(field.label()),
+      description: field.description(),
+      default: processed.default_value.as_ref(),
+      ..Default::default()
+    };
+
+    match field.variant() {
+      FieldVariant::Text | FieldVariant::TextArea | FieldVariant::Hidden => {
+        schema.schema_type = Some("string");
+      }
+      FieldVariant::Dropdown | FieldVariant::Radio => {
+        schema.schema_type = Some("string");
+        schema.enumeration = field.options().map(values);
+      }
+      FieldVariant::Multiselect | FieldVariant::Ranking => {
+        schema.schema_type = Some("array");
+        schema.items = Some(Box::new(Schema {
+          schema_type: Some("string"),
+          enumeration: field.options().map(values),
+          ..Default::default()
+        }));
+        schema.unique_items = Some(true);
+        // Without a limit every option has to be put in order
+        if let (FieldVariant::Ranking, None, Some(opts)) =
+          (field.variant(), processed.select_to_rank, field.options())
+        {
+          schema.min_items = Some(opts.len() as u64);
+          schema.max_items = Some(opts.len() as u64);
+        }
+      }
+      FieldVariant::Matrix => {
+        let columns = field.options().map(values);
+        let rows = field.matrix_rows().unwrap_or_default();
+        schema.schema_type = Some("object");
+        schema.properties = Some(Properties(
+          rows
+            .iter()
+            .map(|row| {
+              let row_schema = Schema {
+                title: Some(&row.label),
+                schema_type: Some("string"),
+                enumeration: columns.clone(),
+                ..Default::default()
+              };
+              (row.value.as_str(), row_schema)
+            })
+            .collect(),
+        ));
+        // A required matrix needs at least one answered row, or all of them when set
+        if let Some(true) = processed.all_rows_required {
+          schema.required = Some(rows.iter().map(|row| row.value.as_str()).collect());
+        } else if field.is_required() {
+          schema.min_properties = Some(1);
+        }
+        schema.additional_properties = Some(false);
+      }
+      FieldVariant::Rating => {
+        schema.schema_type = Some("integer");
+        if let Some(ref rating) = processed.rating {
+          schema.minimum = rating.min;
+          schema.maximum = rating.max;
+        }
+      }
+      FieldVariant::Boolean => {
+        schema.schema_type = Some("boolean");
+        // A required consent has to be given, not only answered
+        if field.is_required() {
+          schema.constant = Some(true);
+        }
+      }
+      FieldVariant::File => {
+        schema.schema_type = Some("array");
+        schema.items = Some(Box::new(Schema::uploaded_file()));
+        if let Some(ref file) = processed.file {
+          if !file.allow_multiple {
+            schema.max_items = Some(1);
+          }
+        }
+      }
+      // Calculated values can be of any type
+      FieldVariant::Calculated => (),
+    }
+
+    for vld in processed.validators.iter().flatten() {
+      match vld.validator_type {
+        ValidatorType::Text => {
+          schema.min_length = vld.min_length;
+          schema.max_length = vld.max_length;
+        }
+        ValidatorType::AnswerCount => {
+          schema.min_items = vld.min_length.or(schema.min_items);
+          schema.max_items = vld.max_length.or(schema.max_items);
+        }
+        ValidatorType::Expression => {
+          if let Some(ExpressionRule::Regex(ref regex)) = vld.rule {
+            schema.pattern = Some(regex);
+          }
+        }
+      }
+    }
+    if schema.max_length.is_none() {
+      schema.max_length = processed.max_length;
+    }
+    // An empty list is no answer to a required question
+    if let (Some("array"), None, true) = (schema.schema_type, schema.min_items, field.is_required())
+    {
+      schema.min_items = Some(1);
+    }
+    schema
+  }
+
+  // SurveyJS stores uploads as a list of file descriptions
+  fn uploaded_file() -> Self {
+    let string = || Schema {
+      schema_type: Some("string"),
+      ..Default::default()
+    };
+    Schema {
+      schema_type: Some("object"),
+      properties: Some(Properties(vec![
+        ("name", string()),
+        ("type", string()),
+        ("content", string()),
+      ])),
+      required: Some(vec!["name", "content"]),
+      ..Default::default()
+    }
+  }
+}
+
+fn values(options: &[OptionType]) -> Vec<&str> {
+  options.iter().map(|opt| opt.value()).collect()
+}
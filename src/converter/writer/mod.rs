@@ -0,0 +1,203 @@
+use crate::converter::error::{ConvertError, Result};
+use crate::converter::field::Page;
+use serde::{Deserialize, Serialize};
+use serde_json::ser::PrettyFormatter;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+
+mod dictionary;
+mod html;
+mod internal;
+mod ir;
+mod json_schema;
+mod survey_expression;
+mod surveyjs;
+mod typescript;
+
+// Renders a parsed page in one output schema
+pub(crate) trait OutputWriter {
+  fn write(&self, page: &Page, options: &OutputOptions, out: &mut dyn Write) -> Result<()>;
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum OutputTarget {
+  #[default]
+  Internal,
+  #[serde(rename = "surveyjs")]
+  SurveyJs,
+  JsonSchema,
+  #[serde(rename = "typescript")]
+  TypeScript,
+  Html,
+  Markdown,
+  Csv,
+  Ir,
+}
+
+// How structured targets are encoded, text targets (TypeScript, HTML, Markdown, CSV) have their own
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum OutputFormat {
+  #[default]
+  Json,
+  CompactJson,
+  Yaml,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct OutputOptions {
+  pub target: OutputTarget,
+  pub format: OutputFormat,
+  // Spaces per level of pretty JSON, 2 when not set
+  pub indent: Option<usize>,
+  pub no_overwrite: bool,
+  pub create_parent_dirs: bool,
+}
+
+impl OutputTarget {
+  fn is_structured(&self) -> bool {
+    matches!(
+      self,
+      OutputTarget::Internal | OutputTarget::SurveyJs | OutputTarget::JsonSchema | OutputTarget::Ir
+    )
+  }
+
+  fn writer(&self) -> Box<dyn OutputWriter> {
+    match self {
+      OutputTarget::Internal => Box::new(internal::InternalWriter),
+      OutputTarget::SurveyJs => Box::new(surveyjs::SurveyJsWriter),
+      OutputTarget::JsonSchema => Box::new(json_schema::JsonSchemaWriter),
+      OutputTarget::TypeScript => Box::new(typescript::TypeScriptWriter),
+      OutputTarget::Html => Box::new(html::HtmlWriter),
+      OutputTarget::Markdown => Box::new(dictionary::MarkdownWriter),
+      OutputTarget::Csv => Box::new(dictionary::CsvWriter),
+      OutputTarget::Ir => Box::new(ir::IrWriter),
+    }
+  }
+}
+
+// Keys come out in declaration order of the output structs whatever the format
+fn serialize<T: Serialize>(value: &T, options: &OutputOptions, out: &mut dyn Write) -> Result<()> {
+  match options.format {
+    OutputFormat::Json => {
+      let indent = vec![b' '; options.indent.unwrap_or(2)];
+      let mut serializer =
+        serde_json::Serializer::with_formatter(out, PrettyFormatter::with_indent(&indent));
+      value.serialize(&mut serializer)?;
+    }
+    OutputFormat::CompactJson => serde_json::to_writer(out, value)?,
+    OutputFormat::Yaml => serde_yaml_ng::to_writer(out, value)?,
+  }
+  Ok(())
+}
+
+pub(crate) fn write_to_file(
+  input: &Page,
+  output_path: &str,
+  options: &OutputOptions,
+) -> Result<()> {
+  if options.format != OutputFormat::Json && !options.target.is_structured() {
+    return Err(ConvertError::UnsupportedFormat(
+      format!("{:?}", options.target),
+      format!("{:?}", options.format),
+    ));
+  }
+  if options.indent.is_some() && options.format != OutputFormat::Json {
+    return Err(ConvertError::UnsupportedIndent(format!(
+      "{:?}",
+      options.format
+    )));
+  }
+  if options.indent.is_some() && !options.target.is_structured() {
+    return Err(ConvertError::UnsupportedIndent(format!(
+      "{:?}",
+      options.target
+    )));
+  }
+  let path = Path::new(output_path);
+  let dir = match path.parent() {
+    Some(dir) if !dir.as_os_str().is_empty() => dir,
+    _ => Path::new("."),
+  };
+  if options.create_parent_dirs {
+    fs::create_dir_all(dir)?;
+  }
+
+  // Written next to the output and renamed over it, so a failed write never leaves a truncated file
+  let (temp_path, temp_file) = create_temp_file(dir, path)?;
+  let written = write_and_sync(input, options, temp_file)
+    .and_then(|_| publish(&temp_path, path, options.no_overwrite));
+  if written.is_err() {
+    let _ = fs::remove_file(&temp_path);
+  }
+  written
+}
+
+fn create_temp_file(dir: &Path, path: &Path) -> Result<(PathBuf, File)> {
+  let name = path
+    .file_name()
+    .map(|n| n.to_string_lossy().into_owned())
+    .unwrap_or_default();
+  let mut attempt = 0;
+  loop {
+    let temp_path = dir.join(format!(".{}.{}.{}.tmp", name, process::id(), attempt));
+    match OpenOptions::new()
+      .write(true)
+      .create_new(true)
+      .open(&temp_path)
+    {
+      Ok(file) => return Ok((temp_path, file)),
+      Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists && attempt < 100 => attempt += 1,
+      Err(err) => return Err(err.into()),
+    }
+  }
+}
+
+// A hard link can't replace an existing file, unlike a rename, so nothing created in the
+// meantime is overwritten either
+fn publish(temp_path: &Path, path: &Path, no_overwrite: bool) -> Result<()> {
+  if !no_overwrite {
+    fs::rename(temp_path, path)?;
+    return Ok(());
+  }
+  match fs::hard_link(temp_path, path) {
+    Ok(()) => {}
+    Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => return Err(output_exists(path)),
+    // Some filesystems have no hard links, the file is then copied into a newly created one
+    Err(_) => copy_new(temp_path, path)?,
+  }
+  let _ = fs::remove_file(temp_path);
+  Ok(())
+}
+
+pub(super) fn copy_new(temp_path: &Path, path: &Path) -> Result<()> {
+  let mut file = match OpenOptions::new().write(true).create_new(true).open(path) {
+    Ok(file) => file,
+    Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => return Err(output_exists(path)),
+    Err(err) => return Err(err.into()),
+  };
+  let copied = File::open(temp_path)
+    .and_then(|mut temp| std::io::copy(&mut temp, &mut file))
+    .and_then(|_| file.sync_all());
+  if let Err(err) = copied {
+    // Only what this call created is removed
+    let _ = fs::remove_file(path);
+    return Err(err.into());
+  }
+  Ok(())
+}
+
+fn output_exists(path: &Path) -> ConvertError {
+  ConvertError::OutputExists(path.to_string_lossy().into_owned())
+}
+
+fn write_and_sync(input: &Page, options: &OutputOptions, file: File) -> Result<()> {
+  let mut out = BufWriter::new(file);
+  options.target.writer().write(input, options, &mut out)?;
+  out.flush()?;
+  out.get_ref().sync_all()?;
+  Ok(())
+}
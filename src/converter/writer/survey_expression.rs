@@ -0,0 +1,424 @@
+use crate::converter::error::{ConvertError, Result};
+
+// Our expressions are the JavaScript the form runs over ${key}. SurveyJS has its own syntax over
+// {key}, so they are parsed and written again, anything without a SurveyJS form is an error
+// instead of an expression SurveyJS can't run.
+
+// A value, like the expression of a calculated field
+pub(super) fn survey_value(expression: &str) -> Result<String> {
+  let expr = Parser::parse(expression)?;
+  Ok(expr.value(expression)?.0)
+}
+
+// A condition, like visibleIf, where a bare answer is true when it is given
+pub(super) fn survey_condition(expression: &str) -> Result<String> {
+  let expr = Parser::parse(expression)?;
+  Ok(expr.condition(expression)?.0)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+  Field(String),
+  Number(String),
+  Str(String),
+  Ident(String),
+  Op(&'static str),
+}
+
+// Longest first, so "===" is not read as "==" and "="
+const OPERATORS: [&str; 19] = [
+  "===", "!==", "==", "!=", "<=", ">=", "&&", "||", "<", ">", "!", "+", "-", "*", "/", "%", "(",
+  ")", ".",
+];
+
+fn tokenize(expression: &str) -> Result<Vec<Token>> {
+  let unsupported = || ConvertError::UnsupportedExpression(expression.to_owned());
+  let chars = expression.chars().collect::<Vec<char>>();
+  let mut tokens = Vec::<Token>::new();
+  let mut i = 0;
+  while i < chars.len() {
+    let c = chars[i];
+    if c.is_whitespace() {
+      i += 1;
+    } else if c == '{' || (c == '$' && chars.get(i + 1) == Some(&'{')) {
+      let start = if c == '$' { i + 2 } else { i + 1 };
+      let end = (start..chars.len())
+        .find(|&j| chars[j] == '}')
+        .ok_or_else(unsupported)?;
+      let key = chars[start..end].iter().collect::<String>();
+      if key.trim().is_empty() {
+        return Err(unsupported());
+      }
+      tokens.push(Token::Field(key.trim().to_owned()));
+      i = end + 1;
+    } else if c.is_ascii_digit() {
+      let end = (i..chars.len())
+        .find(|&j| !(chars[j].is_ascii_digit() || chars[j] == '.'))
+        .unwrap_or(chars.len());
+      tokens.push(Token::Number(chars[i..end].iter().collect()));
+      i = end;
+    } else if c == '\'' || c == '"' {
+      let end = (i + 1..chars.len())
+        .find(|&j| chars[j] == c)
+        .ok_or_else(unsupported)?;
+      let text = chars[i + 1..end].iter().collect::<String>();
+      if text.contains('\\') {
+        return Err(unsupported());
+      }
+      tokens.push(Token::Str(text));
+      i = end + 1;
+    } else if c.is_alphabetic() || c == '_' {
+      let end = (i..chars.len())
+        .find(|&j| !(chars[j].is_alphanumeric() || chars[j] == '_'))
+        .unwrap_or(chars.len());
+      tokens.push(Token::Ident(chars[i..end].iter().collect()));
+      i = end;
+    } else {
+      let rest = chars[i..].iter().collect::<String>();
+      let op = OPERATORS
+        .iter()
+        .find(|op| rest.starts_with(*op))
+        .ok_or_else(unsupported)?;
+      tokens.push(Token::Op(op));
+      i += op.chars().count();
+    }
+  }
+  Ok(tokens)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BinaryOp {
+  Or,
+  And,
+  Equal,
+  NotEqual,
+  Less,
+  LessOrEqual,
+  Greater,
+  GreaterOrEqual,
+  Add,
+  Subtract,
+  Multiply,
+  Divide,
+  Modulo,
+}
+
+impl BinaryOp {
+  fn from_token(op: &str) -> Option<Self> {
+    match op {
+      "||" => Some(BinaryOp::Or),
+      "&&" => Some(BinaryOp::And),
+      "===" | "==" => Some(BinaryOp::Equal),
+      "!==" | "!=" => Some(BinaryOp::NotEqual),
+      "<" => Some(BinaryOp::Less),
+      "<=" => Some(BinaryOp::LessOrEqual),
+      ">" => Some(BinaryOp::Greater),
+      ">=" => Some(BinaryOp::GreaterOrEqual),
+      "+" => Some(BinaryOp::Add),
+      "-" => Some(BinaryOp::Subtract),
+      "*" => Some(BinaryOp::Multiply),
+      "/" => Some(BinaryOp::Divide),
+      "%" => Some(BinaryOp::Modulo),
+      _ => None,
+    }
+  }
+
+  fn precedence(self) -> u8 {
+    match self {
+      BinaryOp::Or => 1,
+      BinaryOp::And => 2,
+      BinaryOp::Equal
+      | BinaryOp::NotEqual
+      | BinaryOp::Less
+      | BinaryOp::LessOrEqual
+      | BinaryOp::Greater
+      | BinaryOp::GreaterOrEqual => 3,
+      BinaryOp::Add | BinaryOp::Subtract => 4,
+      BinaryOp::Multiply | BinaryOp::Divide | BinaryOp::Modulo => 5,
+    }
+  }
+
+  fn symbol(self) -> &'static str {
+    match self {
+      BinaryOp::Or => "or",
+      BinaryOp::And => "and",
+      BinaryOp::Equal => "=",
+      BinaryOp::NotEqual => "!=",
+      BinaryOp::Less => "<",
+      BinaryOp::LessOrEqual => "<=",
+      BinaryOp::Greater => ">",
+      BinaryOp::GreaterOrEqual => ">=",
+      BinaryOp::Add => "+",
+      BinaryOp::Subtract => "-",
+      BinaryOp::Multiply => "*",
+      BinaryOp::Divide => "/",
+      BinaryOp::Modulo => "%",
+    }
+  }
+
+  // The comparison that holds exactly when this one doesn't
+  fn inverse(self) -> Option<Self> {
+    match self {
+      BinaryOp::Equal => Some(BinaryOp::NotEqual),
+      BinaryOp::NotEqual => Some(BinaryOp::Equal),
+      BinaryOp::Less => Some(BinaryOp::GreaterOrEqual),
+      BinaryOp::LessOrEqual => Some(BinaryOp::Greater),
+      BinaryOp::Greater => Some(BinaryOp::LessOrEqual),
+      BinaryOp::GreaterOrEqual => Some(BinaryOp::Less),
+      _ => None,
+    }
+  }
+
+  fn is_logical(self) -> bool {
+    matches!(self, BinaryOp::Or | BinaryOp::And)
+  }
+
+  // "a and (b and c)" reads the same without parentheses, "a - (b - c)" doesn't
+  fn right_precedence(self) -> u8 {
+    if self.is_logical() {
+      self.precedence()
+    } else {
+      self.precedence() + 1
+    }
+  }
+
+  fn is_arithmetic(self) -> bool {
+    self.precedence() >= 4
+  }
+}
+
+#[derive(Debug)]
+enum Expr {
+  Field(String),
+  Length(String),
+  Number(String),
+  Str(String),
+  Bool(bool),
+  Not(Box<Expr>),
+  Negative(Box<Expr>),
+  Binary(Box<Expr>, BinaryOp, Box<Expr>),
+  Contains(Box<Expr>, Box<Expr>),
+}
+
+// Precedence of what is written, to know when a part needs parentheses
+const ATOM: u8 = 6;
+const COMPARISON: u8 = 3;
+
+struct Parser<'a> {
+  expression: &'a str,
+  tokens: Vec<Token>,
+  pos: usize,
+}
+
+impl<'a> Parser<'a> {
+  fn parse(expression: &'a str) -> Result<Expr> {
+    let mut parser = Parser {
+      expression,
+      tokens: tokenize(expression)?,
+      pos: 0,
+    };
+    let expr = parser.binary(1)?;
+    if parser.pos < parser.tokens.len() {
+      return Err(parser.unsupported());
+    }
+    Ok(expr)
+  }
+
+  fn unsupported(&self) -> ConvertError {
+    ConvertError::UnsupportedExpression(self.expression.to_owned())
+  }
+
+  fn peek(&self) -> Option<&Token> {
+    self.tokens.get(self.pos)
+  }
+
+  fn next(&mut self) -> Result<Token> {
+    let token = self.peek().cloned().ok_or_else(|| self.unsupported())?;
+    self.pos += 1;
+    Ok(token)
+  }
+
+  fn expect(&mut self, op: &str) -> Result<()> {
+    match self.next()? {
+      Token::Op(o) if o == op => Ok(()),
+      _ => Err(self.unsupported()),
+    }
+  }
+
+  // Operators of at least the given precedence, all of them are left associative
+  fn binary(&mut self, min_precedence: u8) -> Result<Expr> {
+    let mut left = self.unary()?;
+    while let Some(Token::Op(op)) = self.peek() {
+      let op = match BinaryOp::from_token(op) {
+        Some(op) if op.precedence() >= min_precedence => op,
+        _ => break,
+      };
+      self.pos += 1;
+      let right = self.binary(op.precedence() + 1)?;
+      left = Expr::Binary(Box::new(left), op, Box::new(right));
+    }
+    Ok(left)
+  }
+
+  fn unary(&mut self) -> Result<Expr> {
+    match self.peek() {
+      Some(Token::Op("!")) => {
+        self.pos += 1;
+        Ok(Expr::Not(Box::new(self.unary()?)))
+      }
+      Some(Token::Op("-")) => {
+        self.pos += 1;
+        Ok(Expr::Negative(Box::new(self.unary()?)))
+      }
+      _ => self.postfix(),
+    }
+  }
+
+  // Only the members the repo writes itself have a SurveyJS form
+  fn postfix(&mut self) -> Result<Expr> {
+    let mut expr = self.primary()?;
+    while let Some(Token::Op(".")) = self.peek() {
+      self.pos += 1;
+      expr = match (self.next()?, expr) {
+        (Token::Ident(member), Expr::Field(key)) if member == "length" => Expr::Length(key),
+        (Token::Ident(member), target) if member == "includes" => {
+          self.expect("(")?;
+          let item = self.binary(1)?;
+          self.expect(")")?;
+          Expr::Contains(Box::new(target), Box::new(item))
+        }
+        _ => return Err(self.unsupported()),
+      };
+    }
+    Ok(expr)
+  }
+
+  fn primary(&mut self) -> Result<Expr> {
+    match self.next()? {
+      Token::Field(key) => Ok(Expr::Field(key)),
+      Token::Number(n) if n.parse::<f64>().is_ok() => Ok(Expr::Number(n)),
+      Token::Str(s) => Ok(Expr::Str(s)),
+      Token::Ident(id) if id == "true" => Ok(Expr::Bool(true)),
+      Token::Ident(id) if id == "false" => Ok(Expr::Bool(false)),
+      Token::Op("(") => {
+        let expr = self.binary(1)?;
+        self.expect(")")?;
+        Ok(expr)
+      }
+      _ => Err(self.unsupported()),
+    }
+  }
+}
+
+fn wrap((text, precedence): (String, u8), min_precedence: u8) -> String {
+  if precedence < min_precedence {
+    format!("({})", text)
+  } else {
+    text
+  }
+}
+
+impl Expr {
+  fn value(&self, expression: &str) -> Result<(String, u8)> {
+    match self {
+      Expr::Field(key) => Ok((format!("{{{}}}", key), ATOM)),
+      Expr::Length(key) => Ok((format!("{{{}.length}}", key), ATOM)),
+      Expr::Number(n) => Ok((n.to_owned(), ATOM)),
+      Expr::Str(s) if !s.contains('\'') => Ok((format!("'{}'", s), ATOM)),
+      Expr::Str(s) if !s.contains('"') => Ok((format!("\"{}\"", s), ATOM)),
+      Expr::Str(_) => Err(ConvertError::UnsupportedExpression(expression.to_owned())),
+      Expr::Bool(b) => Ok((b.to_string(), ATOM)),
+      Expr::Not(inner) => inner.negation(expression),
+      Expr::Negative(inner) => match inner.as_ref() {
+        Expr::Number(n) => Ok((format!("-{}", n), ATOM)),
+        _ => Ok((
+          format!("0 - {}", wrap(inner.value(expression)?, ATOM)),
+          BinaryOp::Subtract.precedence(),
+        )),
+      },
+      Expr::Binary(left, op, right) => {
+        let (left, right) = if op.is_logical() {
+          (left.condition(expression)?, right.condition(expression)?)
+        } else {
+          (left.value(expression)?, right.value(expression)?)
+        };
+        Ok((
+          format!(
+            "{} {} {}",
+            wrap(left, op.precedence()),
+            op.symbol(),
+            wrap(right, op.right_precedence())
+          ),
+          op.precedence(),
+        ))
+      }
+      Expr::Contains(target, item) => Ok((
+        format!(
+          "{} contains {}",
+          wrap(target.value(expression)?, COMPARISON + 1),
+          wrap(item.value(expression)?, COMPARISON + 1)
+        ),
+        COMPARISON,
+      )),
+    }
+  }
+
+  fn condition(&self, expression: &str) -> Result<(String, u8)> {
+    let unsupported = || ConvertError::UnsupportedExpression(expression.to_owned());
+    match self {
+      Expr::Field(key) => Ok((format!("{{{}}} notempty", key), COMPARISON)),
+      Expr::Length(key) => Ok((format!("{{{}.length}} > 0", key), COMPARISON)),
+      Expr::Number(_) | Expr::Str(_) | Expr::Negative(_) => Err(unsupported()),
+      Expr::Binary(_, op, _) if op.is_arithmetic() => Err(unsupported()),
+      _ => self.value(expression),
+    }
+  }
+
+  // SurveyJS has no plain "not", so the negation is pushed down to the comparisons
+  fn negation(&self, expression: &str) -> Result<(String, u8)> {
+    let unsupported = || ConvertError::UnsupportedExpression(expression.to_owned());
+    match self {
+      Expr::Field(key) => Ok((format!("{{{}}} empty", key), COMPARISON)),
+      Expr::Length(key) => Ok((format!("{{{}.length}} = 0", key), COMPARISON)),
+      Expr::Bool(b) => Ok(((!b).to_string(), ATOM)),
+      Expr::Not(inner) => inner.condition(expression),
+      Expr::Binary(left, op, right) => {
+        let (negated_op, left, right) = match op {
+          BinaryOp::Or => (
+            BinaryOp::And,
+            left.negation(expression)?,
+            right.negation(expression)?,
+          ),
+          BinaryOp::And => (
+            BinaryOp::Or,
+            left.negation(expression)?,
+            right.negation(expression)?,
+          ),
+          _ => (
+            op.inverse().ok_or_else(unsupported)?,
+            left.value(expression)?,
+            right.value(expression)?,
+          ),
+        };
+        Ok((
+          format!(
+            "{} {} {}",
+            wrap(left, negated_op.precedence()),
+            negated_op.symbol(),
+            wrap(right, negated_op.right_precedence())
+          ),
+          negated_op.precedence(),
+        ))
+      }
+      Expr::Contains(target, item) => Ok((
+        format!(
+          "{} notcontains {}",
+          wrap(target.value(expression)?, COMPARISON + 1),
+          wrap(item.value(expression)?, COMPARISON + 1)
+        ),
+        COMPARISON,
+      )),
+      Expr::Number(_) | Expr::Str(_) | Expr::Negative(_) => Err(unsupported()),
+    }
+  }
+}
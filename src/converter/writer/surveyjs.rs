@@ -0,0 +1,470 @@
+use super::survey_expression::{survey_condition, survey_value};
+use super::{serialize, OutputOptions, OutputWriter};
+use crate::converter::error::Result;
+use crate::converter::field::element::{escape_html, Content, Element, Panel, PanelVariant};
+use crate::converter::field::subtypes::{
+  ExpressionRule, FieldVariant, NumInputSpec, OptionType, Validator, ValidatorType,
+};
+use crate::converter::field::{Field, Page};
+use serde::Serialize;
+use serde_json::Value;
+use std::io::Write;
+
+// The official SurveyJS JSON, so the output can be passed to a SurveyJS model as is
+pub(super) struct SurveyJsWriter;
+
+impl OutputWriter for SurveyJsWriter {
+  fn write(&self, page: &Page, options: &OutputOptions, out: &mut dyn Write) -> Result<()> {
+    serialize(&Survey::from_page(page)?, options, out)?;
+    Ok(())
+  }
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Survey<'a> {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  title: Option<&'a str>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  description: Option<&'a str>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  completed_html: Option<String>,
+
+  pages: Vec<SurveyPage<'a>>,
+}
+
+#[derive(Serialize, Debug)]
+struct SurveyPage<'a> {
+  name: &'static str,
+  elements: Vec<SurveyElement<'a>>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(untagged)]
+enum SurveyElement<'a> {
+  Question(Box<Question<'a>>),
+  Panel(SurveyPanel<'a>),
+  Html(Html<'a>),
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct SurveyPanel<'a> {
+  #[serde(rename = "type")]
+  panel_type: &'static str,
+
+  name: &'a str,
+
+  title: &'a str,
+
+  #[serde(skip_serializing_if = "is_false")]
+  is_required: bool,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  visible_if: Option<String>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  min_panel_count: Option<u64>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  max_panel_count: Option<u64>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  elements: Option<Vec<SurveyElement<'a>>>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  template_elements: Option<Vec<SurveyElement<'a>>>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Html<'a> {
+  #[serde(rename = "type")]
+  html_type: &'static str,
+
+  name: &'a str,
+
+  html: &'a str,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  visible_if: Option<String>,
+}
+
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct Question<'a> {
+  #[serde(rename = "type")]
+  question_type: &'static str,
+
+  name: &'a str,
+
+  title: &'a str,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  description: Option<&'a str>,
+
+  #[serde(skip_serializing_if = "is_false")]
+  is_required: bool,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  visible: Option<bool>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  visible_if: Option<String>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  default_value: Option<&'a Value>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  placeholder: Option<&'a str>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  max_length: Option<u64>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  choices: Option<Vec<Choice<'a>>>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  choices_from_question: Option<&'a str>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  choices_from_question_mode: Option<&'static str>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  columns: Option<Vec<Choice<'a>>>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  rows: Option<Vec<Choice<'a>>>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  is_all_row_required: Option<bool>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  rate_min: Option<u64>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  rate_max: Option<u64>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  rate_step: Option<u64>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  min_rate_description: Option<&'a str>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  max_rate_description: Option<&'a str>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  label_true: Option<&'a str>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  label_false: Option<&'a str>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  accepted_types: Option<&'a str>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  max_size: Option<u64>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  allow_multiple: Option<bool>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  select_to_rank_enabled: Option<bool>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  expression: Option<String>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  validators: Option<Vec<SurveyValidator<'a>>>,
+}
+
+#[derive(Serialize, Debug)]
+struct Choice<'a> {
+  value: &'a str,
+  text: &'a str,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct SurveyValidator<'a> {
+  #[serde(rename = "type")]
+  validator_type: &'static str,
+
+  text: &'a str,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  min_length: Option<u64>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  max_length: Option<u64>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  min_count: Option<u64>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  max_count: Option<u64>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  regex: Option<&'a str>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  expression: Option<String>,
+}
+
+impl<'a> Survey<'a> {
+  fn from_page(page: &'a Page) -> Result<Self> {
+    let metadata = page.metadata();
+    Ok(Survey {
+      title: metadata.title.as_deref(),
+      description: metadata.description.as_deref(),
+      completed_html: metadata
+        .completion_text
+        .as_ref()
+        .map(|text| format!("<p>{}</p>", escape_html(text).replace('\n', "<br>"))),
+      pages: vec![SurveyPage {
+        name: "page1",
+        elements: survey_elements(page.elements(), false)?,
+      }],
+    })
+  }
+}
+
+fn survey_elements(elements: &[Element], in_dynamic_panel: bool) -> Result<Vec<SurveyElement<'_>>> {
+  elements
+    .iter()
+    .map(|element| match element {
+      Element::Field(f) => Ok(SurveyElement::Question(Box::new(Question::from_field(
+        f,
+        in_dynamic_panel,
+      )?))),
+      Element::Panel(p) => Ok(SurveyElement::Panel(SurveyPanel::from_panel(p)?)),
+      Element::Content(c) => Ok(SurveyElement::Html(Html::from_content(c)?)),
+    })
+    .collect()
+}
+
+impl<'a> SurveyPanel<'a> {
+  fn from_panel(panel: &'a Panel) -> Result<Self> {
+    let mut survey_panel = SurveyPanel {
+      panel_type: "panel",
+      name: panel.name(),
+      title: panel.title(),
+      is_required: panel.is_required(),
+      visible_if: panel.visible().map(survey_condition).transpose()?,
+      min_panel_count: None,
+      max_panel_count: None,
+      elements: None,
+      template_elements: None,
+    };
+    match panel.variant() {
+      PanelVariant::Panel => {
+        survey_panel.elements = Some(survey_elements(panel.elements(), false)?)
+      }
+      PanelVariant::Dynamic => {
+        survey_panel.panel_type = "paneldynamic";
+        survey_panel.min_panel_count = panel.min_count();
+        survey_panel.max_panel_count = panel.max_count();
+        survey_panel.template_elements = Some(survey_elements(panel.elements(), true)?);
+      }
+    }
+    Ok(survey_panel)
+  }
+}
+
+impl<'a> Html<'a> {
+  fn from_content(content: &'a Content) -> Result<Self> {
+    Ok(Html {
+      html_type: "html",
+      name: content.name(),
+      html: content.html(),
+      visible_if: content.visible().map(survey_condition).transpose()?,
+    })
+  }
+}
+
+impl<'a> Question<'a> {
+  fn from_field(field: &'a Field, in_dynamic_panel: bool) -> Result<Self> {
+    let processed = field.processed();
+    // Inside a dynamic panel the field is referred to through the current panel item
+    let key = if in_dynamic_panel {
+      format!("panel.{}", field.field_name())
+    } else {
+      field.field_name().to_owned()
+    };
+    let mut question = Question {
+      question_type: survey_type(field.variant()),
+      name: field.field_name(),
+      title: field.label(),
+      description: field.description(),
+      is_required: field.is_required(),
+      visible_if: processed
+        .visible
+        .as_deref()
+        .map(survey_condition)
+        .transpose()?,
+      default_value: processed.default_value.as_ref(),
+      placeholder: processed
+        .placeholder
+        .as_deref()
+        .or(processed.options_caption.as_deref()),
+      max_length: processed.max_length,
+      label_true: processed.label_true.as_deref(),
+      label_false: processed.label_false.as_deref(),
+      select_to_rank_enabled: processed.select_to_rank,
+      expression: processed
+        .expression
+        .as_deref()
+        .map(survey_value)
+        .transpose()?,
+      validators: processed
+        .validators
+        .as_ref()
+        .map(|vlds| {
+          vlds
+            .iter()
+            .map(|vld| SurveyValidator::from_validator(vld, &key))
+            .collect::<Result<Vec<SurveyValidator>>>()
+        })
+        .transpose()?,
+      ..Default::default()
+    };
+
+    match field.variant() {
+      FieldVariant::Matrix => {
+        question.columns = field.options().map(choices);
+        question.rows = field.matrix_rows().map(choices);
+        question.is_all_row_required = processed.all_rows_required;
+      }
+      // Both keep a value in the answers without being shown
+      FieldVariant::Hidden | FieldVariant::Calculated => question.visible = Some(false),
+      _ => question.choices = field.options().map(choices),
+    }
+
+    // Options copied from the answer of another question
+    if let Some(opt_from_key) = field.options_from_key() {
+      question.choices_from_question = Some(opt_from_key.trim_start_matches("panel."));
+      question.choices_from_question_mode = Some("selected");
+    }
+    if let Some(ref rating) = processed.rating {
+      question.rate_min = rating.min;
+      question.rate_max = rating.max;
+      question.rate_step = rating.step;
+      question.min_rate_description = rating.min_label.as_deref();
+      question.max_rate_description = rating.max_label.as_deref();
+    }
+    if let Some(ref file) = processed.file {
+      question.accepted_types = file.accepted_types.as_deref();
+      question.max_size = file.max_size;
+      question.allow_multiple = Some(file.allow_multiple);
+    }
+    Ok(question)
+  }
+}
+
+impl<'a> SurveyValidator<'a> {
+  fn from_validator(validator: &'a Validator, key: &str) -> Result<Self> {
+    let mut survey_validator = SurveyValidator {
+      validator_type: "text",
+      text: &validator.text,
+      min_length: None,
+      max_length: None,
+      min_count: None,
+      max_count: None,
+      regex: None,
+      expression: None,
+    };
+    match validator.validator_type {
+      ValidatorType::Text => {
+        survey_validator.min_length = validator.min_length;
+        survey_validator.max_length = validator.max_length;
+      }
+      ValidatorType::AnswerCount => {
+        survey_validator.validator_type = "answercount";
+        survey_validator.min_count = validator.min_length;
+        survey_validator.max_count = validator.max_length;
+      }
+      ValidatorType::Expression => match validator.rule {
+        Some(ExpressionRule::Regex(ref regex)) => {
+          survey_validator.validator_type = "regex";
+          survey_validator.regex = Some(regex);
+        }
+        ref rule => {
+          survey_validator.validator_type = "expression";
+          survey_validator.expression = match rule {
+            Some(ExpressionRule::Range(spec)) => Some(range_expression(key, spec)),
+            Some(ExpressionRule::Exclusive(values)) => Some(exclusive_expression(key, values)),
+            Some(ExpressionRule::Checked) => Some(format!("{{{}}} = true", key)),
+            _ => validator
+              .expression
+              .as_deref()
+              .map(survey_condition)
+              .transpose()?,
+          };
+        }
+      },
+    }
+    Ok(survey_validator)
+  }
+}
+
+fn survey_type(variant: &FieldVariant) -> &'static str {
+  match variant {
+    FieldVariant::Dropdown => "dropdown",
+    FieldVariant::Text => "text",
+    FieldVariant::TextArea => "comment",
+    FieldVariant::Radio => "radiogroup",
+    FieldVariant::Multiselect => "checkbox",
+    FieldVariant::Matrix => "matrix",
+    FieldVariant::Rating => "rating",
+    FieldVariant::Boolean => "boolean",
+    FieldVariant::File => "file",
+    FieldVariant::Ranking => "ranking",
+    FieldVariant::Hidden => "text",
+    FieldVariant::Calculated => "expression",
+  }
+}
+
+fn choices(options: &[OptionType]) -> Vec<Choice<'_>> {
+  options
+    .iter()
+    .map(|opt| Choice {
+      value: &opt.value,
+      text: &opt.label,
+    })
+    .collect()
+}
+
+fn range_expression(key: &str, spec: &NumInputSpec) -> String {
+  let mut conditions = Vec::<String>::new();
+  if let Some(mn) = spec.min {
+    let op = if mn.inclusive { ">=" } else { ">" };
+    conditions.push(format!("{{{}}} {} {}", key, op, mn.value));
+  }
+  if let Some(mx) = spec.max {
+    let op = if mx.inclusive { "<=" } else { "<" };
+    conditions.push(format!("{{{}}} {} {}", key, op, mx.value));
+  }
+  conditions.join(" and ")
+}
+
+// An exclusive option may only be chosen on its own
+fn exclusive_expression(key: &str, values: &[String]) -> String {
+  let none_chosen = values
+    .iter()
+    .map(|v| format!("{{{}}} notcontains '{}'", key, v))
+    .collect::<Vec<String>>()
+    .join(" and ");
+  let only_one = values
+    .iter()
+    .map(|v| format!("{{{}}} = ['{}']", key, v))
+    .collect::<Vec<String>>()
+    .join(" or ");
+  format!("({}) or {}", none_chosen, only_one)
+}
+
+fn is_false(b: &bool) -> bool {
+  !b
+}
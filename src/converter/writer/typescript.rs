@@ -0,0 +1,143 @@
+use super::{OutputOptions, OutputWriter};
+use crate::converter::error::Result;
+use crate::converter::field::element::{Element, PanelVariant};
+use crate::converter::field::subtypes::{FieldVariant, OptionType};
+use crate::converter::field::{Field, Page};
+use std::io::Write;
+
+const INDENT: &str = "  ";
+
+// A .d.ts declaring the answers object of the form
+pub(super) struct TypeScriptWriter;
+
+impl OutputWriter for TypeScriptWriter {
+  fn write(&self, page: &Page, _options: &OutputOptions, out: &mut dyn Write) -> Result<()> {
+    let mut lines = Vec::<String>::new();
+    if let Some(ref title) = page.metadata().title {
+      lines.extend(doc_comment(title, None, ""));
+    }
+    lines.push("export interface Answers {".to_owned());
+    lines.extend(members(page.elements(), 1));
+    lines.push("}".to_owned());
+    writeln!(out, "{}", lines.join("\n"))?;
+    Ok(())
+  }
+}
+
+// Fields of a plain panel are answered at the level of the panel itself
+fn members(elements: &[Element], depth: usize) -> Vec<String> {
+  let indent = INDENT.repeat(depth);
+  let mut lines = Vec::<String>::new();
+  for element in elements.iter() {
+    match element {
+      Element::Field(f) => {
+        lines.extend(doc_comment(f.label(), f.description(), &indent));
+        lines.push(format!(
+          "{}{}{}: {}",
+          indent,
+          f.field_name(),
+          optional_marker(f.is_required()),
+          answer_type(f, depth)
+        ));
+      }
+      Element::Panel(p) => match p.variant() {
+        PanelVariant::Panel => lines.extend(members(p.elements(), depth)),
+        PanelVariant::Dynamic => {
+          lines.extend(doc_comment(p.title(), None, &indent));
+          lines.push(format!(
+            "{}{}{}: Array<{{",
+            indent,
+            p.name(),
+            optional_marker(p.is_required())
+          ));
+          lines.extend(members(p.elements(), depth + 1));
+          lines.push(format!("{}}}>", indent));
+        }
+      },
+      Element::Content(_) => (),
+    }
+  }
+  lines
+}
+
+fn answer_type(field: &Field, depth: usize) -> String {
+  match field.variant() {
+    FieldVariant::Text | FieldVariant::TextArea | FieldVariant::Hidden => "string".to_owned(),
+    FieldVariant::Dropdown | FieldVariant::Radio => literal_union(field.options()),
+    FieldVariant::Multiselect | FieldVariant::Ranking => "string[]".to_owned(),
+    FieldVariant::Matrix => {
+      let indent = INDENT.repeat(depth);
+      let column_type = literal_union(field.options());
+      let row_marker = optional_marker(field.processed().all_rows_required == Some(true));
+      let mut lines = vec!["{".to_owned()];
+      for row in field.matrix_rows().unwrap_or_default() {
+        lines.extend(doc_comment(
+          &row.label,
+          None,
+          &format!("{}{}", indent, INDENT),
+        ));
+        lines.push(format!(
+          "{}{}{}{}: {}",
+          indent, INDENT, row.value, row_marker, column_type
+        ));
+      }
+      lines.push(format!("{}}}", indent));
+      lines.join("\n")
+    }
+    FieldVariant::Rating => "number".to_owned(),
+    // A required consent has to be given, not only answered
+    FieldVariant::Boolean if field.is_required() => "true".to_owned(),
+    FieldVariant::Boolean => "boolean".to_owned(),
+    FieldVariant::File => "Array<{ name: string; type: string; content: string }>".to_owned(),
+    FieldVariant::Calculated => "unknown".to_owned(),
+  }
+}
+
+fn literal_union(options: Option<&[OptionType]>) -> String {
+  match options {
+    Some(opts) if !opts.is_empty() => opts
+      .iter()
+      .map(|opt| string_literal(opt.value()))
+      .collect::<Vec<String>>()
+      .join(" | "),
+    // Options copied from another answer are only known when the form is filled in
+    _ => "string".to_owned(),
+  }
+}
+
+fn string_literal(s: &str) -> String {
+  let escaped = s
+    .replace('\\', "\\\\")
+    .replace('\'', "\\'")
+    .replace('\n', "\\n");
+  format!("'{}'", escaped)
+}
+
+fn optional_marker(is_required: bool) -> &'static str {
+  if is_required {
+    ""
+  } else {
+    "?"
+  }
+}
+
+fn doc_comment(label: &str, description: Option<&str>, indent: &str) -> Vec<String> {
+  let text = match description {
+    Some(desc) => format!("{}\n\n{}", label, desc),
+    None => label.to_owned(),
+  };
+  let text = text.replace("*/", "*\\/");
+  if !text.contains('\n') {
+    return vec![format!("{}/** {} */", indent, text)];
+  }
+  let mut lines = vec![format!("{}/**", indent)];
+  for line in text.lines() {
+    if line.is_empty() {
+      lines.push(format!("{} *", indent));
+    } else {
+      lines.push(format!("{} * {}", indent, line));
+    }
+  }
+  lines.push(format!("{} */", indent));
+  lines
+}
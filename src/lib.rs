@@ -19,5 +19,6 @@ static ALLOC: mimalloc::MiMalloc = mimalloc::MiMalloc;
 #[module_exports]
 fn init(mut exports: JsObject) -> Result<()> {
   exports.create_named_method("convertAsync", converter::convert_async)?;
+  exports.create_named_method("checkAsync", converter::check_async)?;
   Ok(())
 }